@@ -0,0 +1,218 @@
+use super::{
+    deal_classes, generate_deck, get_hand, hand_rank, Card, Hand, HandRank, PaytableError, Rational,
+};
+
+/// Pays of the head to head Ante/Play game, in units of the ante.
+///
+/// The player antes, sees three cards and either folds, losing the ante, or
+/// plays by betting the ante again. The ante bonus is paid on the player's
+/// hand whenever they play. The dealer needs Queen high or better to qualify,
+/// otherwise the ante wins even money and the play bet pushes. Against a
+/// qualifying dealer the better hand wins both bets and a tie pushes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AntePlayRules {
+    /// bonus paid to one on the player's hand, in the order of the three
+    /// card [`Hand::categories`]
+    ante_bonus: Vec<usize>,
+    /// pays to one on a winning play bet
+    play_win: usize,
+}
+impl AntePlayRules {
+    /// Fails unless there is an ante bonus for every three card category
+    pub fn new(ante_bonus: impl Into<Vec<usize>>, play_win: usize) -> Result<Self, PaytableError> {
+        let ante_bonus = ante_bonus.into();
+        let expected = Hand::categories(3).len();
+        if ante_bonus.len() != expected {
+            return Err(PaytableError::WrongCount {
+                expected,
+                found: ante_bonus.len(),
+            });
+        }
+        Ok(Self {
+            ante_bonus,
+            play_win,
+        })
+    }
+    pub fn ante_bonus(&self) -> &[usize] {
+        &self.ante_bonus
+    }
+    pub fn play_win(&self) -> usize {
+        self.play_win
+    }
+}
+impl Default for AntePlayRules {
+    /// The common 5-4-1 ante bonus with an even money play bet
+    fn default() -> Self {
+        Self {
+            ante_bonus: vec![5, 4, 1, 0, 0, 0],
+            play_win: 1,
+        }
+    }
+}
+/// Best decision for one class of player hands
+#[derive(Clone, Debug)]
+pub struct AntePlayDecision {
+    pub cards: [Card; 3],
+    /// number of unordered deals in the class
+    pub weight: usize,
+    /// expected net win per ante when playing the hand
+    pub play_return: Rational,
+    /// whether playing beats folding, folding always returns `-1`
+    pub play: bool,
+}
+/// Exact analysis of the Ante/Play game over every player and dealer hand
+#[derive(Clone, Debug)]
+pub struct AntePlayAnalysis {
+    /// expected net win per ante under the optimal strategy
+    pub expected_return: Rational,
+    /// probability that the optimal player plays
+    pub play_frequency: Rational,
+    /// weakest player hand that should be played
+    pub threshold: [Card; 3],
+    pub decisions: Vec<AntePlayDecision>,
+}
+impl AntePlayAnalysis {
+    /// House edge per ante, the negated expected return
+    pub fn house_edge(&self) -> Rational {
+        -self.expected_return
+    }
+}
+/// Dealer qualifies with Queen high or better
+fn qualifies(rank: &HandRank) -> bool {
+    *rank
+        >= HandRank {
+            hand: Hand::HighCard,
+            kickers: [12, 0, 0],
+        }
+}
+/// Solves the game exactly, every player hand class against all C(49, 3)
+/// dealer hands from the rest of the deck
+pub fn analyze_ante_play(rules: &AntePlayRules) -> AntePlayAnalysis {
+    let deck = generate_deck();
+    let index = |card: &Card| deck.iter().position(|c| c == card).unwrap();
+    // showdown rank of every hand, by deck index of the cards
+    let mut ranks = vec![
+        HandRank {
+            hand: Hand::HighCard,
+            kickers: [0; 3],
+        };
+        deck.len() * deck.len() * deck.len()
+    ];
+    for i in 0..deck.len() {
+        for j in i + 1..deck.len() {
+            for k in j + 1..deck.len() {
+                ranks[(i * deck.len() + j) * deck.len() + k] =
+                    hand_rank(&[deck[i], deck[j], deck[k]]);
+            }
+        }
+    }
+    let play_win = rules.play_win as i128;
+    let decisions = deal_classes::<3>()
+        .into_iter()
+        .map(|class| {
+            let player = hand_rank(&class.cards);
+            let bonus = rules.ante_bonus[get_hand(&class.cards).index(3)] as i128;
+            let held = class.cards.map(|card| index(&card));
+            let rest = (0..deck.len())
+                .filter(|i| !held.contains(i))
+                .collect::<Vec<_>>();
+            let mut total = 0i128;
+            let mut hands = 0i128;
+            for a in 0..rest.len() {
+                for b in a + 1..rest.len() {
+                    for c in b + 1..rest.len() {
+                        let dealer = ranks[(rest[a] * deck.len() + rest[b]) * deck.len() + rest[c]];
+                        total += bonus
+                            + if !qualifies(&dealer) {
+                                1
+                            } else if player > dealer {
+                                1 + play_win
+                            } else if player < dealer {
+                                -2
+                            } else {
+                                0
+                            };
+                        hands += 1;
+                    }
+                }
+            }
+            let play_return = Rational::new(total, hands);
+            AntePlayDecision {
+                cards: class.cards,
+                weight: class.weight,
+                play: play_return >= Rational::from_integer(-1),
+                play_return,
+            }
+        })
+        .collect::<Vec<_>>();
+    let deals: usize = decisions.iter().map(|decision| decision.weight).sum();
+    let weighted = |value: Rational, weight: usize| value * Rational::from_integer(weight as i128);
+    let expected_return = decisions
+        .iter()
+        .map(|decision| {
+            let value = if decision.play {
+                decision.play_return
+            } else {
+                Rational::from_integer(-1)
+            };
+            weighted(value, decision.weight)
+        })
+        .sum::<Rational>()
+        / deals as i128;
+    let play_frequency = Rational::new(
+        decisions
+            .iter()
+            .filter(|decision| decision.play)
+            .map(|decision| decision.weight as i128)
+            .sum(),
+        deals as i128,
+    );
+    let threshold = decisions
+        .iter()
+        .filter(|decision| decision.play)
+        .min_by_key(|decision| hand_rank(&decision.cards))
+        .expect("some hand is always worth playing")
+        .cards;
+    AntePlayAnalysis {
+        expected_return,
+        play_frequency,
+        threshold,
+        decisions,
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parse_hand, Rank};
+    #[test]
+    fn dealer_qualifies() {
+        let rank = |s| hand_rank(&parse_hand::<3>(s).unwrap());
+        assert!(qualifies(&rank("Qc 4d 2h")));
+        assert!(qualifies(&rank("2c 2d 3h")));
+        assert!(!qualifies(&rank("Jc 9d 7h")));
+    }
+    #[test]
+    fn standard_game() {
+        let analysis = analyze_ante_play(&AntePlayRules::default());
+        // the well known 3.37% house edge and Q-6-4 playing threshold
+        let edge = crate::to_f64(&analysis.house_edge());
+        assert!((edge - 0.0337).abs() < 0.00005, "{}", edge);
+        let mut ranks = analysis.threshold.map(|card| card.rank);
+        ranks.sort();
+        assert_eq!(ranks, [Rank::Four, Rank::Six, Rank::Queen]);
+    }
+    #[test]
+    fn short_bonus() {
+        assert!(matches!(
+            AntePlayRules::new([1], 1),
+            Err(PaytableError::WrongCount {
+                expected: 6,
+                found: 1
+            })
+        ));
+        assert_eq!(
+            AntePlayRules::new([5, 4, 1, 0, 0, 0], 1).unwrap(),
+            AntePlayRules::default()
+        );
+    }
+}
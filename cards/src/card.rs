@@ -0,0 +1,173 @@
+use super::{Rank, Suit};
+use std::{fmt, str::FromStr};
+
+/// Cards order by rank first, then suit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+/// Builds a standard 52 card deck, ordered by suit then rank. See
+/// [`Deck`](crate::Deck) for shoes and jokers.
+pub fn generate_deck() -> Vec<Card> {
+    (Suit::Clubs..=Suit::Diamonds)
+        .flat_map(|suit| (Rank::A..=Rank::King).map(move |rank| Card { rank, suit }))
+        .collect::<Vec<_>>()
+}
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// there was no card to parse
+    Empty,
+    InvalidRank(String),
+    InvalidSuit(String),
+    /// a hand had the wrong number of cards
+    WrongCount {
+        expected: usize,
+        found: usize,
+    },
+    /// a hand listed the same card twice
+    Duplicate(Card),
+    /// a hand listed a card more often than the deck holds it
+    NotInDeck(Card),
+}
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no card given"),
+            Self::InvalidRank(rank) => write!(f, "invalid rank: {}", rank),
+            Self::InvalidSuit(suit) => write!(f, "invalid suit: {}", suit),
+            Self::WrongCount { expected, found } => {
+                write!(f, "expected {} cards, found {}", expected, found)
+            }
+            Self::Duplicate(card) => write!(f, "{} is listed twice", card),
+            Self::NotInDeck(card) => write!(f, "the deck does not hold that many {}", card),
+        }
+    }
+}
+impl std::error::Error for ParseCardError {}
+/// Cards print as rank then suit, e.g. `Tc`, and `{:#}` uses suit glyphs, `T♣`
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{}{:#}", self.rank, self.suit)
+        } else {
+            write!(f, "{}{}", self.rank, self.suit)
+        }
+    }
+}
+/// Parses a rank followed by a suit, such as `AS`, `10h`, `Td` or `K♣`
+impl FromStr for Card {
+    type Err = ParseCardError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (split, _suit) = s.char_indices().last().ok_or(ParseCardError::Empty)?;
+        let (rank, suit) = s.split_at(split);
+        if rank.is_empty() {
+            return Err(ParseCardError::InvalidRank(rank.to_string()));
+        }
+        Ok(Card {
+            rank: rank.parse()?,
+            suit: suit.parse()?,
+        })
+    }
+}
+/// Parses cards separated by whitespace or commas, repeats allowed
+pub(crate) fn parse_card_list(s: &str) -> Result<Vec<Card>, ParseCardError> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|card| !card.is_empty())
+        .map(str::parse)
+        .collect()
+}
+/// Parses cards separated by whitespace or commas, e.g. `8c 9c Tc`
+pub fn parse_cards(s: &str) -> Result<Vec<Card>, ParseCardError> {
+    let cards = parse_card_list(s)?;
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].contains(card) {
+            return Err(ParseCardError::Duplicate(*card));
+        }
+    }
+    Ok(cards)
+}
+/// Parses a hand of `N` cards such as `8c 9c Tc`
+pub fn parse_hand<const N: usize>(s: &str) -> Result<[Card; N], ParseCardError> {
+    let cards = parse_cards(s)?;
+    let found = cards.len();
+    cards
+        .try_into()
+        .map_err(|_| ParseCardError::WrongCount { expected: N, found })
+}
+/// Prints cards separated by spaces, `{:#}` prints them with suit glyphs
+pub struct Cards<'a>(pub &'a [Card]);
+impl fmt::Display for Cards<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, card) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            if f.alternate() {
+                write!(f, "{:#}", card)?;
+            } else {
+                write!(f, "{}", card)?;
+            }
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn parse_notation() {
+        let ten = Card {
+            rank: Rank::Ten,
+            suit: Suit::Hearts,
+        };
+        assert_eq!("10h".parse(), Ok(ten));
+        assert_eq!("TH".parse(), Ok(ten));
+        assert_eq!("t♥".parse(), Ok(ten));
+        assert_eq!(
+            "AS".parse(),
+            Ok(Card {
+                rank: Rank::A,
+                suit: Suit::Spades
+            })
+        );
+        assert_eq!(
+            "1s".parse::<Card>(),
+            Err(ParseCardError::InvalidRank("1".to_string()))
+        );
+        assert_eq!(
+            "Kx".parse::<Card>(),
+            Err(ParseCardError::InvalidSuit("x".to_string()))
+        );
+        assert_eq!(
+            "h".parse::<Card>(),
+            Err(ParseCardError::InvalidRank("".to_string()))
+        );
+        assert_eq!("".parse::<Card>(), Err(ParseCardError::Empty));
+    }
+    #[test]
+    fn round_trip() {
+        for card in generate_deck() {
+            assert_eq!(card.to_string().parse(), Ok(card));
+            assert_eq!(format!("{:#}", card).parse(), Ok(card));
+        }
+        let hand = parse_hand::<3>("8c 9c 10c").unwrap();
+        assert_eq!(Cards(&hand).to_string(), "8c 9c Tc");
+        assert_eq!(format!("{:#}", Cards(&hand)), "8♣ 9♣ T♣");
+    }
+    #[test]
+    fn parse_hands() {
+        assert_eq!(
+            parse_hand::<3>("8c 9c"),
+            Err(ParseCardError::WrongCount {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            parse_hand::<3>("8c,9c,8c"),
+            Err(ParseCardError::Duplicate("8c".parse().unwrap()))
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use super::{
+    hand::{rank_counts, Evaluator},
+    Card, Hand,
+};
+use std::{fmt, sync::Arc};
+
+/// Test of whether natural cards make a custom category
+type CardTest = Arc<dyn Fn(&[Card]) -> bool + Send + Sync>;
+/// How a category decides which hands it holds
+#[derive(Clone)]
+enum Predicate {
+    Standard(Hand),
+    Custom(CardTest),
+}
+/// A named hand category a game's hands are sorted into. Categories are
+/// checked from the highest priority down and a hand takes the first one it
+/// makes, so a predicate only needs to describe its own cards: a "Mini Royal"
+/// of suited A-K-Q ranked above straight flushes need not exclude anything.
+#[derive(Clone)]
+pub struct Category {
+    /// name used in paytable files and reports, e.g. `mini_royal`
+    pub name: String,
+    /// higher priorities are checked, and rank, first
+    pub priority: i32,
+    predicate: Predicate,
+}
+impl Category {
+    /// Category of the hands `predicate` accepts. Wild cards have already
+    /// been resolved into natural cards when it is called.
+    pub fn new(
+        name: impl Into<String>,
+        priority: i32,
+        predicate: impl Fn(&[Card]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            predicate: Predicate::Custom(Arc::new(predicate)),
+        }
+    }
+    /// One of the standard categories, made as the game's [`Evaluator`]
+    /// makes it, under the paytable name of `hand`
+    pub fn standard(hand: Hand, priority: i32) -> Self {
+        Self {
+            name: hand.name().to_string(),
+            priority,
+            predicate: Predicate::Standard(hand),
+        }
+    }
+    /// The standard hand this category stands for, if any
+    pub fn hand(&self) -> Option<Hand> {
+        match self.predicate {
+            Predicate::Standard(hand) => Some(hand),
+            Predicate::Custom(_) => None,
+        }
+    }
+    /// Whether natural `cards` make this category regardless of any other
+    pub fn matches<const N: usize>(&self, evaluator: &Evaluator, cards: &[Card; N]) -> bool {
+        self.matches_counted(evaluator, cards, &rank_counts(cards))
+    }
+    fn matches_counted<const N: usize>(
+        &self,
+        evaluator: &Evaluator,
+        cards: &[Card; N],
+        counts: &[usize],
+    ) -> bool {
+        match &self.predicate {
+            Predicate::Standard(hand) => hand.matches(evaluator, cards, counts),
+            Predicate::Custom(predicate) => predicate(cards),
+        }
+    }
+}
+impl fmt::Debug for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Category")
+            .field("name", &self.name)
+            .field("priority", &self.priority)
+            .field("hand", &self.hand())
+            .finish()
+    }
+}
+impl PartialEq for Category {
+    /// Custom categories are equal only when they share a predicate
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.priority == other.priority
+            && match (&self.predicate, &other.predicate) {
+                (Predicate::Standard(a), Predicate::Standard(b)) => a == b,
+                (Predicate::Custom(a), Predicate::Custom(b)) => Arc::ptr_eq(a, b),
+                _ => false,
+            }
+    }
+}
+impl Eq for Category {}
+/// The categories of a game, best first. Tables and paytables are indexed by
+/// position in this list, so they size themselves from it.
+///
+/// ```
+/// use cs411_cards::{Categories, Category, Rank};
+/// // suited A-K-Q above every straight flush
+/// let categories = Categories::standard(3).with(Category::new("mini_royal", 700, |cards| {
+///     let mut ranks = cards.iter().map(|card| card.rank).collect::<Vec<_>>();
+///     ranks.sort();
+///     ranks == [Rank::A, Rank::Queen, Rank::King] && cards.iter().all(|c| c.suit == cards[0].suit)
+/// }));
+/// assert_eq!(categories.names()[..2], ["mini_royal", "straight_flush"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Categories {
+    /// sorted by priority, highest first
+    list: Vec<Category>,
+}
+impl Categories {
+    /// The [`Hand::categories`] of hands of `size` cards, spaced a hundred
+    /// apart from 100 for high card up, so others can be slotted between.
+    ///
+    /// Panics if `size` is not in [`Hand::SIZES`].
+    pub fn standard(size: usize) -> Self {
+        let hands = Hand::categories(size);
+        Self {
+            list: hands
+                .iter()
+                .enumerate()
+                .map(|(i, hand)| Category::standard(*hand, 100 * (hands.len() - i) as i32))
+                .collect(),
+        }
+    }
+    /// Adds `category` in priority order, after any of equal priority. A
+    /// category of the same name is replaced.
+    pub fn with(self, category: Category) -> Self {
+        let mut list = self.without(&category.name).list;
+        let at = list
+            .iter()
+            .position(|other| other.priority < category.priority)
+            .unwrap_or(list.len());
+        list.insert(at, category);
+        Self { list }
+    }
+    /// Removes the category called `name`
+    pub fn without(mut self, name: &str) -> Self {
+        self.list.retain(|category| category.name != name);
+        self
+    }
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Category> {
+        self.list.iter()
+    }
+    /// Names of the categories, best first
+    pub fn names(&self) -> Vec<&str> {
+        self.list
+            .iter()
+            .map(|category| category.name.as_str())
+            .collect()
+    }
+    /// Index of the category called `name`
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.list.iter().position(|category| category.name == name)
+    }
+    /// Index of the best category `cards` make, wild cards playing as
+    /// whatever makes the best one.
+    ///
+    /// Panics if the cards make no category, a list needs a last category
+    /// every hand makes, as high card is.
+    pub fn classify<const N: usize>(&self, evaluator: &Evaluator, cards: &[Card; N]) -> usize {
+        let mut best = self.list.len();
+        evaluator.for_each_resolution(cards, |hand| {
+            let counts = rank_counts(hand);
+            if let Some(index) = self.list[..best]
+                .iter()
+                .position(|category| category.matches_counted(evaluator, hand, &counts))
+            {
+                best = index;
+            }
+        });
+        assert!(best < self.list.len(), "{:?} make no category", cards);
+        best
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parse_hand, Deck, Game, GameOutcomes, Paytable, Rank, Rational};
+    fn mini_royal() -> Category {
+        Category::new("mini_royal", 650, |cards| {
+            let mut ranks = cards.iter().map(|card| card.rank).collect::<Vec<_>>();
+            ranks.sort();
+            ranks == [Rank::A, Rank::Queen, Rank::King]
+                && cards.iter().all(|card| card.suit == cards[0].suit)
+        })
+    }
+    #[test]
+    fn standard_order() {
+        let evaluator = Evaluator::default();
+        for size in Hand::SIZES {
+            let categories = Categories::standard(size);
+            assert_eq!(categories.len(), Hand::categories(size).len());
+            for (i, hand) in Hand::categories(size).iter().enumerate() {
+                assert_eq!(categories.position(hand.name()), Some(i));
+            }
+        }
+        let cards = parse_hand::<3>("8c 9c Tc").unwrap();
+        assert_eq!(Categories::standard(3).classify(&evaluator, &cards), 0);
+    }
+    #[test]
+    fn custom_categories() {
+        let categories = Categories::standard(3)
+            .with(mini_royal())
+            .with(Category::new("queens_or_better", 250, |cards| {
+                cards.iter().any(|card| {
+                    (card.rank >= Rank::Queen || card.rank == Rank::A)
+                        && cards.iter().filter(|c| c.rank == card.rank).count() >= 2
+                })
+            }));
+        assert_eq!(
+            categories.names(),
+            [
+                "mini_royal",
+                "straight_flush",
+                "three_of_a_kind",
+                "straight",
+                "flush",
+                "queens_or_better",
+                "pair",
+                "high_card"
+            ]
+        );
+        let game = Game::standard().with_categories(categories.clone());
+        let table = game.all_deals::<3>();
+        assert_eq!(table.counts().len(), 8);
+        // one suited A-K-Q per suit, taken from the straight flushes, and
+        // six pairs of each of queens, kings and aces with 48 kickers
+        assert_eq!(table.counts()[..2], [4, 44]);
+        assert_eq!(table.counts()[5..], [3 * 6 * 48, 10 * 6 * 48, 16440]);
+        assert_eq!(table.total(), 22100);
+        // jokers make the mini royal from any two of its cards
+        let wild = Game::with_jokers(1).with_categories(categories.clone());
+        let cards = wild.deck.parse_hand::<3>("Ah Kh Xc").unwrap();
+        assert_eq!(categories.classify(&wild.evaluator, &cards), 0);
+        let paytable =
+            Paytable::for_categories("mini", 3, &categories, [200, 40, 30, 6, 4, 2, 1, 0]).unwrap();
+        assert_eq!(paytable.pay_for("mini_royal"), Some(200));
+        assert_eq!(Paytable::from_toml(&paytable.to_toml()).unwrap(), paytable);
+        let play = game.optimal_play(parse_hand::<3>("Ad Kd 2d").unwrap(), &paytable);
+        // holding the suited ace and king draws to the mini royal
+        assert_eq!(play.hold.iter().flatten().count(), 2);
+        assert_eq!(
+            play.expected_return,
+            Rational::new(200 + 3 * 6 + 9 * 4 + 6 * 2, 49)
+        );
+        let outcomes = GameOutcomes::<3>::for_game(&game);
+        assert!(
+            outcomes.expected_return(&paytable)
+                > Game::standard()
+                    .expected_return(&Paytable::new("standard", [40, 30, 6, 4, 1, 0]).unwrap())
+        );
+    }
+    #[test]
+    fn suited_trips_in_a_shoe() {
+        let categories =
+            Categories::standard(3).with(Category::new("suited_trips", 550, |cards| {
+                cards.iter().all(|card| *card == cards[0])
+            }));
+        let game = Game::from_deck(Deck::shoe(2)).with_categories(categories);
+        let table = game.all_deals::<3>();
+        // two copies of each card never make three of it
+        assert_eq!(table.counts()[1], 0);
+        let game = Game::from_deck(Deck::shoe(3)).with_categories(game.categories.unwrap());
+        let table = game.all_deals::<3>();
+        assert_eq!(table.counts()[1], 52);
+        assert_eq!(table.counts()[2], 13 * (220 - 4));
+    }
+}
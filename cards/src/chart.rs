@@ -0,0 +1,430 @@
+use super::{
+    strategy::hold_masks, to_f64, Card, Categories, Evaluator, GameOutcomes, Paytable, Rational,
+};
+use std::collections::HashMap;
+
+/// One line of a strategy chart
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChartRule {
+    /// the kind of hold, e.g. `2 to a straight flush, 1 gap`
+    pub description: String,
+    /// probability that a deal is played by this rule
+    pub frequency: Rational,
+}
+/// Ranked list of holds read off the optimal strategy, such as "made
+/// straight flush" before "2 to a straight flush, no gaps". A hand is
+/// played by the first rule one of its holds follows, and when several of
+/// its holds follow that rule the one keeping more cards, then the earlier
+/// cards, is kept.
+///
+/// Rules describe holds by their shape rather than by exact cards, so the
+/// chart can not always match perfect play, and
+/// [`StrategyChart::cost`] is the exact return it gives up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StrategyChart {
+    /// rules best first, each played on at least one deal
+    pub rules: Vec<ChartRule>,
+    /// expected return per credit bet of perfect play
+    pub optimal_return: Rational,
+    /// expected return per credit bet when playing by the chart
+    pub chart_return: Rational,
+}
+/// Holds of one deal class, by rule
+struct ClassHolds {
+    weight: i128,
+    /// every rule the class has a hold for, with the expected pay of the
+    /// first hold following it
+    options: Vec<(usize, Rational)>,
+}
+impl ClassHolds {
+    /// Expected pay of the class's first hold following `rule`
+    fn pay(&self, rule: usize) -> Option<Rational> {
+        self.options
+            .iter()
+            .find(|(other, _)| *other == rule)
+            .map(|(_, pay)| *pay)
+    }
+    /// Expected pay of the class played by rules ranked by `rank`
+    fn played(&self, rank: &[usize]) -> (usize, Rational) {
+        *self
+            .options
+            .iter()
+            .min_by_key(|(rule, _)| rank[*rule])
+            .expect("every class has holds")
+    }
+}
+impl StrategyChart {
+    /// Derives the chart for `paytable` from the draw outcomes of a game.
+    ///
+    /// Rules are ordered greedily, each placed above the others by how much
+    /// return that saves on the deals where both apply, then adjacent rules
+    /// are swapped while that raises the chart's exact return.
+    pub fn new<const N: usize>(outcomes: &GameOutcomes<N>, paytable: &Paytable) -> Self {
+        let (evaluator, categories) = (outcomes.evaluator(), outcomes.categories());
+        let mut names = vec![];
+        let mut ids = HashMap::new();
+        let mut optimal = Rational::from_integer(0);
+        let classes = outcomes
+            .classes()
+            .iter()
+            .map(|outcome| {
+                let weight = outcome.class.weight as i128;
+                optimal += outcome.play(paytable).expected_return * weight;
+                let mut options: Vec<(usize, Rational)> = vec![];
+                for mask in hold_masks::<N>() {
+                    if outcome.holds[mask].total() == 0 {
+                        continue;
+                    }
+                    let hold: [Option<Card>; N] = std::array::from_fn(|i| {
+                        (mask & (1 << i) != 0).then_some(outcome.class.cards[i])
+                    });
+                    let description = Self::describe(evaluator, categories, &hold);
+                    let id = *ids.entry(description.clone()).or_insert_with(|| {
+                        names.push(description);
+                        names.len() - 1
+                    });
+                    if options.iter().all(|(rule, _)| *rule != id) {
+                        let pay = outcome.holds[mask].calculate_return_full(paytable);
+                        options.push((id, pay));
+                    }
+                }
+                ClassHolds { weight, options }
+            })
+            .collect::<Vec<_>>();
+        let order = Self::order(&classes, names.len());
+        let mut rank = vec![usize::MAX; names.len()];
+        for (i, rule) in order.iter().enumerate() {
+            rank[*rule] = i;
+        }
+        let mut played = vec![0i128; names.len()];
+        let mut total = Rational::from_integer(0);
+        for class in classes.iter() {
+            let (rule, pay) = class.played(&rank);
+            played[rule] += class.weight;
+            total += pay * class.weight;
+        }
+        let deals = outcomes.deals() as i128;
+        let scale = Rational::new(1, deals * paytable.bet as i128);
+        Self {
+            rules: order
+                .into_iter()
+                .filter(|rule| played[*rule] > 0)
+                .map(|rule| ChartRule {
+                    description: names[rule].clone(),
+                    frequency: Rational::new(played[rule], deals),
+                })
+                .collect(),
+            optimal_return: optimal * scale,
+            chart_return: total * scale,
+        }
+    }
+    /// Ranks the rules that are best for some class. Rules are taken
+    /// greedily, the one that loses least when placed above every rule left
+    /// first, then single rules are moved wherever that raises the chart's
+    /// return until no move does. Last, rules are sorted by the mean return
+    /// of their holds wherever that costs nothing, so "made straight flush"
+    /// reads above "made straight".
+    fn order(classes: &[ClassHolds], rules: usize) -> Vec<usize> {
+        // preference[a][b], return lost by ranking `b` above `a`
+        let mut preference = vec![vec![0.0; rules]; rules];
+        let mut remaining = vec![false; rules];
+        let mut values = vec![];
+        for class in classes.iter() {
+            let weight = class.weight as f64;
+            let options = class
+                .options
+                .iter()
+                .map(|(rule, pay)| (*rule, to_f64(pay) * weight))
+                .collect::<Vec<_>>();
+            let best =
+                options.iter().fold(
+                    options[0],
+                    |best, option| if option.1 > best.1 { *option } else { best },
+                );
+            remaining[best.0] = true;
+            for (rule, pay) in options.iter() {
+                preference[best.0][*rule] += best.1 - pay;
+            }
+            values.push(options);
+        }
+        let mut order = vec![];
+        while let Some(next) = (0..rules).filter(|rule| remaining[*rule]).min_by(|a, b| {
+            let lost = |rule: usize| -> f64 {
+                (0..rules)
+                    .filter(|other| remaining[*other])
+                    .map(|other| preference[other][rule])
+                    .sum()
+            };
+            let saved = |rule: usize| -> f64 {
+                (0..rules)
+                    .filter(|other| remaining[*other])
+                    .map(|other| preference[rule][other])
+                    .sum()
+            };
+            lost(*a)
+                .total_cmp(&lost(*b))
+                .then(saved(*b).total_cmp(&saved(*a)))
+        }) {
+            remaining[next] = false;
+            order.push(next);
+        }
+        let value = |order: &[usize]| -> f64 {
+            let mut rank = vec![usize::MAX; rules];
+            for (i, rule) in order.iter().enumerate() {
+                rank[*rule] = i;
+            }
+            values
+                .iter()
+                .map(|options| {
+                    options
+                        .iter()
+                        .min_by_key(|(rule, _)| rank[*rule])
+                        .expect("every class has holds")
+                        .1
+                })
+                .sum()
+        };
+        let mut best = value(&order);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for from in 0..order.len() {
+                for to in 0..order.len() {
+                    let mut moved = order.clone();
+                    let rule = moved.remove(from);
+                    moved.insert(to, rule);
+                    let moved_value = value(&moved);
+                    // float sums of the same pays, only clear gains count
+                    if moved_value > best * (1.0 + 1e-12) + 1e-9 {
+                        (order, best, improved) = (moved, moved_value, true);
+                    }
+                }
+            }
+        }
+        let mut rank = vec![usize::MAX; rules];
+        for (i, rule) in order.iter().enumerate() {
+            rank[*rule] = i;
+        }
+        let mut played = classes
+            .iter()
+            .map(|class| class.played(&rank).0)
+            .collect::<Vec<_>>();
+        let (mut pay, mut weight) = (vec![0.0; rules], vec![0.0; rules]);
+        for options in values.iter() {
+            for (rule, rule_pay) in options.iter() {
+                pay[*rule] += rule_pay;
+            }
+        }
+        for class in classes.iter() {
+            for (rule, _) in class.options.iter() {
+                weight[*rule] += class.weight as f64;
+            }
+        }
+        let mean = |rule: usize| match weight[rule] {
+            0.0 => f64::NEG_INFINITY,
+            weight => pay[rule] / weight,
+        };
+        // insertion sort by swapping neighbours, as long as the deals handed
+        // from the upper rule to the lower one return exactly as much
+        for i in 1..order.len() {
+            let mut at = i;
+            while at > 0 && mean(order[at]) > mean(order[at - 1]) {
+                let (above, below) = (order[at - 1], order[at]);
+                let moved = (0..classes.len())
+                    .filter(|i| played[*i] == above && classes[*i].pay(below).is_some())
+                    .collect::<Vec<_>>();
+                let change = moved
+                    .iter()
+                    .map(|i| {
+                        let class = &classes[*i];
+                        (class.pay(below).unwrap() - class.pay(above).unwrap()) * class.weight
+                    })
+                    .sum::<Rational>();
+                if change < Rational::from_integer(0) {
+                    break;
+                }
+                for i in moved {
+                    played[i] = below;
+                }
+                order.swap(at - 1, at);
+                at -= 1;
+            }
+        }
+        order
+    }
+    /// Return per credit bet the chart gives up against perfect play
+    pub fn cost(&self) -> Rational {
+        self.optimal_return - self.chart_return
+    }
+    /// The hold the chart plays for `cards`, holding nothing if no rule
+    /// applies, which never happens for hands of the game the chart was
+    /// derived for
+    pub fn hold<const N: usize>(
+        &self,
+        evaluator: &Evaluator,
+        categories: &Categories,
+        cards: &[Card; N],
+    ) -> [Option<Card>; N] {
+        hold_masks::<N>()
+            .into_iter()
+            .map(|mask| std::array::from_fn(|i| (mask & (1 << i) != 0).then_some(cards[i])))
+            .filter_map(|hold| {
+                let description = Self::describe(evaluator, categories, &hold);
+                let rule = self
+                    .rules
+                    .iter()
+                    .position(|rule| rule.description == description)?;
+                Some((rule, hold))
+            })
+            .min_by_key(|(rule, _)| *rule)
+            .map_or([None; N], |(_, hold)| hold)
+    }
+    /// Describes the shape of a hold, e.g. `made flush`, `pair`,
+    /// `2 to a straight, 1 gap` or `single K`, with any wild cards counted
+    /// at the end
+    pub fn describe<const N: usize>(
+        evaluator: &Evaluator,
+        categories: &Categories,
+        hold: &[Option<Card>; N],
+    ) -> String {
+        let held = hold.iter().flatten().copied().collect::<Vec<_>>();
+        if held.is_empty() {
+            return "draw every card".to_string();
+        }
+        if let Some(cards) = hold.iter().copied().collect::<Option<Vec<_>>>() {
+            let cards: [Card; N] = cards.try_into().expect("a hold has N cards");
+            let name = categories.names()[categories.classify(evaluator, &cards)];
+            return format!("made {}", name.replace('_', " "));
+        }
+        let naturals = held
+            .iter()
+            .filter(|card| !evaluator.is_wild(card))
+            .copied()
+            .collect::<Vec<_>>();
+        let wild = held.len() - naturals.len();
+        let mut counts = naturals
+            .iter()
+            .map(|card| naturals.iter().filter(|c| c.rank == card.rank).count())
+            .collect::<Vec<_>>();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        let suited = naturals.iter().all(|card| card.suit == naturals[0].suit);
+        let gaps = |gaps: u32| match gaps {
+            0 => "no gaps".to_string(),
+            1 => "1 gap".to_string(),
+            gaps => format!("{} gaps", gaps),
+        };
+        let natural = match counts[..] {
+            [] => None,
+            [_] => Some(format!("single {}", naturals[0].rank)),
+            [n, ..] if n >= 2 => {
+                // counts hold each rank once per card of it
+                let second = counts.get(n).copied().unwrap_or(0);
+                let (name, sets) = match (n, second) {
+                    (4.., _) => ("four of a kind", n),
+                    (3, 2..) => ("full house", n + second),
+                    (3, _) => ("three of a kind", n),
+                    (2, 2) => ("two pair", 4),
+                    _ => ("pair", 2),
+                };
+                Some(match naturals.len() - sets {
+                    0 => name.to_string(),
+                    1 => format!("{} and a kicker", name),
+                    kickers => format!("{} and {} kickers", name, kickers),
+                })
+            }
+            _ => Some(match (suited, evaluator.straight_gaps(&naturals, N)) {
+                (true, Some(n)) => format!("{} to a straight flush, {}", naturals.len(), gaps(n)),
+                (true, None) => format!("{} to a flush", naturals.len()),
+                (false, Some(n)) => format!("{} to a straight, {}", naturals.len(), gaps(n)),
+                (false, None) => format!("{} unrelated cards", naturals.len()),
+            }),
+        };
+        match (natural, wild) {
+            (Some(natural), 0) => natural,
+            (Some(natural), wild) => format!("{} and {} wild", natural, wild),
+            (None, wild) => format!("{} wild", wild),
+        }
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parse_hand, strategy::hold_mask, Game};
+    #[test]
+    fn describe() {
+        let evaluator = Evaluator::deuces_wild();
+        let categories = Categories::standard(3);
+        let describe = |hand: &str, hold: [bool; 3]| {
+            let cards = parse_hand::<3>(hand).unwrap();
+            let hold: [Option<Card>; 3] = std::array::from_fn(|i| hold[i].then_some(cards[i]));
+            StrategyChart::describe(&evaluator, &categories, &hold)
+        };
+        assert_eq!(describe("8c 9c Tc", [true; 3]), "made straight flush");
+        assert_eq!(
+            describe("8c 9c Tc", [true, false, true]),
+            "2 to a straight flush, 1 gap"
+        );
+        assert_eq!(
+            describe("8c 9d Tc", [true, true, false]),
+            "2 to a straight, no gaps"
+        );
+        // Q-K-A runs, so the ace and king are connected
+        assert_eq!(
+            describe("Ac Kc 5d", [true, true, false]),
+            "2 to a straight flush, no gaps"
+        );
+        assert_eq!(describe("8c 3c Td", [true, true, false]), "2 to a flush");
+        assert_eq!(describe("8c 8d Td", [true, true, false]), "pair");
+        assert_eq!(
+            describe("8c 2d Td", [true, true, false]),
+            "single 8 and 1 wild"
+        );
+        assert_eq!(describe("8c 2d Td", [false; 3]), "draw every card");
+    }
+    #[test]
+    fn assignment_chart() {
+        let outcomes = GameOutcomes::<3>::new();
+        let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+        let chart = StrategyChart::new(&outcomes, &paytable);
+        assert_eq!(chart.optimal_return, outcomes.expected_return(&paytable));
+        assert!(chart.cost() >= Rational::from_integer(0));
+        assert!(chart.cost() < Rational::new(1, 40));
+        let place = |description: &str| {
+            chart
+                .rules
+                .iter()
+                .position(|rule| rule.description == description)
+                .unwrap()
+        };
+        assert!(place("made straight flush") < place("2 to a straight flush, no gaps"));
+        // made hands no other hold competes with read best first
+        assert!(place("made straight flush") < place("made three of a kind"));
+        assert!(place("made three of a kind") < place("made straight"));
+        assert_eq!(
+            chart
+                .rules
+                .iter()
+                .map(|rule| rule.frequency)
+                .sum::<Rational>(),
+            Rational::from_integer(1)
+        );
+        // playing every class by the chart gives its return
+        let game = Game::standard();
+        let categories = game.hand_categories(3);
+        let mut total = Rational::from_integer(0);
+        for outcome in outcomes.classes() {
+            let hold = chart.hold(&game.evaluator, &categories, &outcome.class.cards);
+            let pay = outcome.holds[hold_mask(&hold)].calculate_return_full(&paytable);
+            total += pay * outcome.class.weight as i128;
+        }
+        assert_eq!(total / outcomes.deals() as i128, chart.chart_return);
+        // pays everything alike, keeping every hand is perfect play
+        let flat = Paytable::new("flat", [2, 2, 2, 2, 2, 2]).unwrap();
+        let chart = StrategyChart::new(&outcomes, &flat);
+        assert_eq!(chart.cost(), Rational::from_integer(0));
+        assert!(chart
+            .rules
+            .iter()
+            .all(|rule| rule.description.starts_with("made")));
+    }
+}
@@ -0,0 +1,153 @@
+use super::{Card, Deck, Game, Rank, Suit};
+use std::collections::HashMap;
+
+/// A dealt hand standing in for every deal that differs from it only by
+/// card order or by renaming suits, all of which play identically
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DealClass<const N: usize = 3> {
+    /// canonical member of the class, sorted
+    pub cards: [Card; N],
+    /// number of unordered deals in the class
+    pub weight: usize,
+}
+/// Calls `f` with every combination of `size` items, each in the order of
+/// `items`
+pub(crate) fn for_each_combination<T: Copy>(items: &[T], size: usize, mut f: impl FnMut(&[T])) {
+    if size > items.len() {
+        return;
+    }
+    let mut picked = (0..size).collect::<Vec<_>>();
+    let mut chosen = picked.iter().map(|i| items[*i]).collect::<Vec<_>>();
+    loop {
+        f(&chosen);
+        // advance the last position that still has room, then reset the ones
+        // after it to follow it
+        let Some(i) = (0..size)
+            .rev()
+            .find(|i| picked[*i] < items.len() - size + i)
+        else {
+            return;
+        };
+        picked[i] += 1;
+        chosen[i] = items[picked[i]];
+        for j in i + 1..size {
+            picked[j] = picked[j - 1] + 1;
+            chosen[j] = items[picked[j]];
+        }
+    }
+}
+/// Calls `f` with every unordered set of `size` cards dealt from `copies`,
+/// distinct cards each with its number of copies, and the number of ways of
+/// dealing it. Copies of a card are interchangeable, so a set holding `k` of
+/// a card's `n` copies is dealt C(n, k) ways.
+pub(crate) fn for_each_deal(
+    copies: &[(Card, usize)],
+    size: usize,
+    mut f: impl FnMut(&[Card], usize),
+) {
+    fn deal(
+        copies: &[(Card, usize)],
+        size: usize,
+        cards: &mut Vec<Card>,
+        ways: usize,
+        f: &mut impl FnMut(&[Card], usize),
+    ) {
+        if cards.len() == size {
+            return f(cards, ways);
+        }
+        for (i, (card, n)) in copies.iter().enumerate() {
+            let start = cards.len();
+            let mut ways = ways;
+            for k in 1..=(*n).min(size - start) {
+                cards.push(*card);
+                ways = ways * (n - k + 1) / k;
+                deal(&copies[i + 1..], size, cards, ways, f);
+            }
+            cards.truncate(start);
+        }
+    }
+    deal(copies, size, &mut Vec::with_capacity(size), 1, &mut f);
+}
+/// Calls `f` with every unordered hand of `N` cards dealt from `copies` and
+/// the number of ways of dealing it
+pub(crate) fn for_each_dealt_hand<const N: usize>(
+    copies: &[(Card, usize)],
+    mut f: impl FnMut([Card; N], usize),
+) {
+    for_each_deal(copies, N, |cards, ways| {
+        f(cards.try_into().expect("deals have N cards"), ways)
+    });
+}
+/// Relabels the suits of `cards` in order of the ranks each suit holds, so
+/// hands that differ only by suit names relabel to the same sorted hand. The
+/// deck's `suits` are handed out in order, so the relabeled hand can still
+/// be dealt. Jokers keep their suit, it only tells them apart.
+fn canonical<const N: usize>(cards: [Card; N], suits: &[Suit]) -> [Card; N] {
+    // copies of each rank per suit, in three bits a rank, so suits holding
+    // more of the higher ranks come first
+    let mut ranks = [0u64; 4];
+    for card in cards.iter().filter(|card| card.rank != Rank::Joker) {
+        ranks[card.suit as usize] += 1 << (3 * card.rank as usize);
+    }
+    let mut order = suits.to_vec();
+    order.sort_by_key(|suit| std::cmp::Reverse(ranks[*suit as usize]));
+    let mut relabel = [Suit::Clubs; 4];
+    for (suit, old) in suits.iter().zip(order) {
+        relabel[old as usize] = *suit;
+    }
+    let mut relabeled = cards.map(|card| match card.rank {
+        Rank::Joker => card,
+        rank => Card {
+            rank,
+            suit: relabel[card.suit as usize],
+        },
+    });
+    relabeled.sort();
+    relabeled
+}
+/// Groups the C(52, N) unordered deals into classes that are equal up to suit
+/// relabeling, for three cards the weights sum to 22,100
+pub fn deal_classes<const N: usize>() -> Vec<DealClass<N>> {
+    Game::standard().deal_classes()
+}
+/// Classes of the deals of `N` cards from `deck`, weighted by the ways of
+/// dealing them
+pub(crate) fn classes<const N: usize>(deck: &Deck) -> Vec<DealClass<N>> {
+    let suits = deck.suits();
+    let mut weights = HashMap::new();
+    for_each_dealt_hand(&deck.copies(), |cards: [Card; N], ways| {
+        *weights.entry(canonical(cards, &suits)).or_insert(0) += ways;
+    });
+    let mut classes = weights
+        .into_iter()
+        .map(|(cards, weight)| DealClass { cards, weight })
+        .collect::<Vec<_>>();
+    classes.sort_by_key(|class| class.cards);
+    classes
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn classes_cover_every_deal() {
+        let classes = deal_classes::<3>();
+        assert_eq!(
+            classes.iter().map(|class| class.weight).sum::<usize>(),
+            22100
+        );
+        assert!(classes.len() < 22100 / 10);
+        let classes = deal_classes::<4>();
+        assert_eq!(
+            classes.iter().map(|class| class.weight).sum::<usize>(),
+            270725
+        );
+        assert_eq!(deal_classes::<5>().len(), 134459);
+        // suit relabeling still applies to a shoe, C(104, 3) deals
+        let classes = Game::shoe(2).deal_classes::<3>();
+        assert_eq!(
+            classes.iter().map(|class| class.weight).sum::<usize>(),
+            182104
+        );
+        assert!(classes.len() < 4000);
+    }
+}
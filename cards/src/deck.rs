@@ -0,0 +1,289 @@
+use super::{card::parse_card_list, generate_deck, Card, ParseCardError, Rank, Rng, Suit};
+
+/// The cards a game deals from, one standard deck or a shoe of several,
+/// possibly with jokers or with ranks and suits left out. A shoe holds
+/// several copies of each card and every copy is dealt on its own, so a hand
+/// may hold the same card twice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deck {
+    /// every card, copies included, sorted
+    cards: Vec<Card>,
+}
+impl Deck {
+    /// A single standard 52 card deck
+    pub fn standard() -> Self {
+        Self::shoe(1)
+    }
+    /// `decks` standard decks dealt together.
+    ///
+    /// Panics if `decks` is zero.
+    pub fn shoe(decks: usize) -> Self {
+        assert!(decks > 0, "a shoe holds at least one deck");
+        Self::from_cards((0..decks).flat_map(|_| generate_deck()).collect())
+    }
+    /// One card of every rank in `ranks` in every suit in `suits`
+    pub fn new(
+        ranks: impl IntoIterator<Item = Rank> + Clone,
+        suits: impl IntoIterator<Item = Suit>,
+    ) -> Self {
+        Self::from_cards(
+            suits
+                .into_iter()
+                .flat_map(|suit| {
+                    ranks
+                        .clone()
+                        .into_iter()
+                        .map(move |rank| Card { rank, suit })
+                })
+                .collect(),
+        )
+    }
+    /// The 36 card short deck, six through ace
+    pub fn short() -> Self {
+        Self::standard().without_ranks(&[Rank::Two, Rank::Three, Rank::Four, Rank::Five])
+    }
+    /// The 40 card Spanish deck, ace through seven and the three court cards
+    /// with the four suits standing in for coins, cups, swords and clubs
+    pub fn spanish() -> Self {
+        Self::standard().without_ranks(&[Rank::Eight, Rank::Nine, Rank::Ten])
+    }
+    /// Strips every card of `ranks` from the deck
+    pub fn without_ranks(mut self, ranks: &[Rank]) -> Self {
+        self.cards.retain(|card| !ranks.contains(&card.rank));
+        self
+    }
+    /// Deck holding exactly `cards`, which may repeat
+    pub fn from_cards(mut cards: Vec<Card>) -> Self {
+        cards.sort();
+        Self { cards }
+    }
+    /// Adds `jokers` wild jokers. Jokers are told apart by suit, a fifth
+    /// joker is another copy of the first.
+    pub fn with_jokers(mut self, jokers: usize) -> Self {
+        self.cards.extend((0..jokers).map(|i| Card {
+            rank: Rank::Joker,
+            suit: Suit::ALL[i % Suit::ALL.len()],
+        }));
+        self.cards.sort();
+        self
+    }
+    /// Every card, copies included
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+    /// Ranks of the natural cards, lowest first
+    pub fn ranks(&self) -> Vec<Rank> {
+        let mut ranks = self
+            .cards
+            .iter()
+            .map(|card| card.rank)
+            .filter(|rank| *rank != Rank::Joker)
+            .collect::<Vec<_>>();
+        ranks.dedup();
+        ranks
+    }
+    /// Suits of the natural cards
+    pub fn suits(&self) -> Vec<Suit> {
+        Suit::ALL
+            .into_iter()
+            .filter(|suit| {
+                self.cards
+                    .iter()
+                    .any(|card| card.rank != Rank::Joker && card.suit == *suit)
+            })
+            .collect()
+    }
+    /// Number of copies of `card` in the deck
+    pub fn copies_of(&self, card: &Card) -> usize {
+        self.cards.iter().filter(|c| *c == card).count()
+    }
+    /// Each distinct card with its number of copies, in card order
+    pub fn copies(&self) -> Vec<(Card, usize)> {
+        let mut copies: Vec<(Card, usize)> = vec![];
+        for card in self.cards.iter() {
+            match copies.last_mut() {
+                Some((last, n)) if last == card => *n += 1,
+                _ => copies.push((*card, 1)),
+            }
+        }
+        copies
+    }
+    /// Whether `cards` can be dealt together, no card more often than the
+    /// deck holds it
+    pub fn can_deal(&self, cards: &[Card]) -> bool {
+        self.find_missing(cards).is_none()
+    }
+    fn find_missing(&self, cards: &[Card]) -> Option<Card> {
+        cards
+            .iter()
+            .find(|card| cards.iter().filter(|c| c == card).count() > self.copies_of(card))
+            .copied()
+    }
+    /// The deck shuffled by a [`Rng`] seeded with `seed`, ready to deal
+    pub fn shuffled(&self, seed: u64) -> ShuffledDeck {
+        ShuffledDeck::new(self.clone(), seed)
+    }
+    /// Parses a hand of `N` cards dealt from this deck, a card may repeat as
+    /// often as the deck holds it
+    pub fn parse_hand<const N: usize>(&self, s: &str) -> Result<[Card; N], ParseCardError> {
+        let cards = parse_card_list(s)?;
+        if let Some(card) = self.find_missing(&cards) {
+            return Err(match self.copies_of(&card) {
+                1 => ParseCardError::Duplicate(card),
+                _ => ParseCardError::NotInDeck(card),
+            });
+        }
+        let found = cards.len();
+        cards
+            .try_into()
+            .map_err(|_| ParseCardError::WrongCount { expected: N, found })
+    }
+}
+impl Default for Deck {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+/// A [`Deck`] in play: cards are dealt and burned off the top of a
+/// Fisher–Yates shuffle. The shuffles only use the seeded [`Rng`], so a seed
+/// replays the same deals on every platform, and [`reset`](Self::reset)
+/// starts them over.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShuffledDeck {
+    deck: Deck,
+    seed: u64,
+    rng: Rng,
+    /// the shuffled cards, the top card first
+    order: Vec<Card>,
+    /// cards dealt or burned so far
+    top: usize,
+}
+impl ShuffledDeck {
+    pub fn new(deck: Deck, seed: u64) -> Self {
+        let mut shuffled = Self {
+            order: deck.cards.clone(),
+            deck,
+            seed,
+            rng: Rng::new(seed),
+            top: 0,
+        };
+        shuffled.shuffle();
+        shuffled
+    }
+    /// The deck being dealt
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// Gathers every card back and shuffles, continuing the seed's numbers
+    pub fn shuffle(&mut self) {
+        self.top = 0;
+        self.rng.shuffle(&mut self.order);
+    }
+    /// Returns every card and restarts the shuffles from the seed, dealing
+    /// again exactly what was dealt since the deck was made
+    pub fn reset(&mut self) {
+        *self = Self::new(self.deck.clone(), self.seed);
+    }
+    /// Deals the top `n` cards, `None` if fewer are left
+    pub fn deal(&mut self, n: usize) -> Option<Vec<Card>> {
+        if n > self.remaining() {
+            return None;
+        }
+        self.top += n;
+        Some(self.order[self.top - n..self.top].to_vec())
+    }
+    /// Deals a hand of the top `N` cards, `None` if fewer are left
+    pub fn deal_hand<const N: usize>(&mut self) -> Option<[Card; N]> {
+        self.deal(N)
+            .map(|cards| cards.try_into().expect("N cards were dealt"))
+    }
+    /// Discards the top card unseen, returning it for the record
+    pub fn burn(&mut self) -> Option<Card> {
+        self.deal(1).map(|cards| cards[0])
+    }
+    /// Number of cards left to deal
+    pub fn remaining(&self) -> usize {
+        self.order.len() - self.top
+    }
+    /// Cards dealt and burned since the last shuffle, in order
+    pub fn dealt(&self) -> &[Card] {
+        &self.order[..self.top]
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn shoe() {
+        let shoe = Deck::shoe(6).with_jokers(2);
+        assert_eq!(shoe.len(), 314);
+        let copies = shoe.copies();
+        assert_eq!(copies.len(), 54);
+        assert!(copies[..52].iter().all(|(_, n)| *n == 6));
+        assert_eq!(
+            shoe.parse_hand::<3>("As As Xc").unwrap(),
+            ["As", "As", "Xc"].map(|card| card.parse().unwrap())
+        );
+        let xs = "Xs".parse().unwrap();
+        assert_eq!(
+            shoe.parse_hand::<3>("As Xs Xc"),
+            Err(ParseCardError::NotInDeck(xs))
+        );
+        assert_eq!(
+            Deck::standard().parse_hand::<3>("As As Kd"),
+            Err(ParseCardError::Duplicate("As".parse().unwrap()))
+        );
+    }
+    #[test]
+    fn dealing() {
+        let mut deck = Deck::standard().shuffled(2024);
+        assert_eq!(deck.remaining(), 52);
+        let hand = deck.deal_hand::<3>().unwrap();
+        // fixed by the seed on every platform
+        assert_eq!(hand, ["Ac", "5d", "Jd"].map(|card| card.parse().unwrap()));
+        let burned = deck.burn().unwrap();
+        let draw = deck.deal(2).unwrap();
+        assert_eq!(deck.remaining(), 46);
+        assert_eq!(deck.dealt()[..3], hand);
+        assert_eq!(deck.dealt()[3], burned);
+        assert_eq!(deck.dealt()[4..], draw);
+        assert_eq!(deck.deal(47), None);
+        assert_eq!(deck.remaining(), 46);
+        let rest = deck.deal(46).unwrap();
+        assert_eq!(deck.burn(), None);
+        let mut cards = deck.dealt().to_vec();
+        cards.sort();
+        assert_eq!(cards, Deck::standard().cards());
+        assert_eq!(rest, deck.dealt()[6..]);
+        // a new shuffle is a new order of the whole deck
+        deck.shuffle();
+        assert_eq!(deck.remaining(), 52);
+        let next = deck.deal_hand::<3>().unwrap();
+        assert_ne!(next, hand);
+        deck.reset();
+        assert_eq!(deck, Deck::standard().shuffled(2024));
+        assert_eq!(deck.deal_hand::<3>(), Some(hand));
+    }
+    #[test]
+    fn compositions() {
+        let short = Deck::short();
+        assert_eq!(short.len(), 36);
+        assert_eq!(short.ranks()[..2], [Rank::A, Rank::Six]);
+        assert_eq!(Deck::spanish().len(), 40);
+        let three_suits = Deck::new(
+            Rank::A..=Rank::King,
+            [Suit::Clubs, Suit::Hearts, Suit::Spades],
+        );
+        assert_eq!(three_suits.len(), 39);
+        assert_eq!(three_suits.suits().len(), 3);
+    }
+}
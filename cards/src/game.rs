@@ -0,0 +1,208 @@
+use super::{
+    deal,
+    strategy::{self, held_cards},
+    Card, Categories, DealClass, Deck, Evaluator, GameOutcomes, HandTable, HoldAdvice, Paytable,
+    Play, Rational,
+};
+
+/// The cards a game deals from and how its hands are evaluated. Hand tables,
+/// deal classes and the solver all work from a game, the free functions such
+/// as [`optimal_play`](crate::optimal_play) play [`Game::standard`].
+///
+/// Deal classes relabel suits, so the natural cards of the deck must hold
+/// the same ranks in every suit, as every [`Deck`] constructor does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub deck: Deck,
+    pub evaluator: Evaluator,
+    /// categories hands are sorted into, `None` for the standard
+    /// [`Hand::categories`](crate::Hand::categories) of each hand size
+    pub categories: Option<Categories>,
+}
+impl Game {
+    /// A standard 52 card deck without wild cards
+    pub fn standard() -> Self {
+        Self::from_deck(Deck::standard())
+    }
+    /// Deals from `deck` with no wild cards but its jokers, straights run
+    /// through the ranks the deck holds
+    pub fn from_deck(deck: Deck) -> Self {
+        Self {
+            evaluator: Evaluator::default().with_ranks(deck.ranks()),
+            deck,
+            categories: None,
+        }
+    }
+    /// A standard deck with `jokers` wild jokers added
+    pub fn with_jokers(jokers: usize) -> Self {
+        Self::from_deck(Deck::standard().with_jokers(jokers))
+    }
+    /// A shoe of `decks` standard decks
+    pub fn shoe(decks: usize) -> Self {
+        Self::from_deck(Deck::shoe(decks))
+    }
+    /// A standard deck with every two wild
+    pub fn deuces_wild() -> Self {
+        Self {
+            evaluator: Evaluator::deuces_wild(),
+            ..Self::standard()
+        }
+    }
+    /// Sorts hands into `categories` instead of the standard ones, whatever
+    /// their size
+    pub fn with_categories(self, categories: Categories) -> Self {
+        Self {
+            categories: Some(categories),
+            ..self
+        }
+    }
+    /// Categories hands of `size` cards are sorted into, best first
+    pub fn hand_categories(&self, size: usize) -> Categories {
+        match &self.categories {
+            Some(categories) => categories.clone(),
+            None => Categories::standard(size),
+        }
+    }
+    /// Counts every unordered deal of `N` cards from the deck
+    pub fn all_deals<const N: usize>(&self) -> HandTable<N> {
+        HandTable::for_game(self)
+    }
+    /// Groups the deals of `N` cards into classes that are equal up to suit
+    /// relabeling
+    pub fn deal_classes<const N: usize>(&self) -> Vec<DealClass<N>> {
+        deal::classes(&self.deck)
+    }
+    /// Best hold for a dealt hand, every hold priced by exact counts of the
+    /// draws left in the deck
+    pub fn optimal_play<const N: usize>(&self, cards: [Card; N], paytable: &Paytable) -> Play<N> {
+        strategy::solve(self, cards, paytable)
+    }
+    /// Prices keeping the cards of `cards` where `hold` is set against the
+    /// optimal hold
+    pub fn hold_cost<const N: usize>(
+        &self,
+        cards: [Card; N],
+        hold: [bool; N],
+        paytable: &Paytable,
+    ) -> HoldAdvice<N> {
+        HoldAdvice::new(self.optimal_play(cards, paytable), held_cards(&cards, hold))
+    }
+    /// Expected return per credit bet when every deal of the paytable's hand
+    /// size is played optimally, see [`GameOutcomes::for_game`] to evaluate
+    /// several paytables
+    pub fn expected_return(&self, paytable: &Paytable) -> Rational {
+        match paytable.cards {
+            3 => GameOutcomes::<3>::for_game(self).expected_return(paytable),
+            4 => GameOutcomes::<4>::for_game(self).expected_return(paytable),
+            5 => GameOutcomes::<5>::for_game(self).expected_return(paytable),
+            cards => unreachable!("paytables are never for {} cards", cards),
+        }
+    }
+}
+impl Default for Game {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{get_hand, parse_hand, Hand, Rank, Suit};
+    #[test]
+    fn joker_deals() {
+        let game = Game::with_jokers(1);
+        assert_eq!(game.deck.len(), 53);
+        let table = game.all_deals::<3>();
+        // the 22,100 natural deals plus C(52, 2) with the joker
+        assert_eq!(table.total(), 23426);
+        let natural = Game::standard().all_deals::<3>();
+        assert!(table.count(Hand::StraightFlush) > natural.count(Hand::StraightFlush));
+        let classes = game.deal_classes::<3>();
+        assert_eq!(
+            classes.iter().map(|class| class.weight).sum::<usize>(),
+            23426
+        );
+        for class in classes.iter() {
+            assert_eq!(
+                get_hand(&class.cards),
+                game.evaluator.get_hand(&class.cards)
+            );
+        }
+    }
+    #[test]
+    fn wild_play() {
+        let paytable = Paytable::new("straight flush", [1, 0, 0, 0, 0, 0]).unwrap();
+        let game = Game::with_jokers(2);
+        let hand = parse_hand::<3>("Xc 8h 9h").unwrap();
+        let play = game.optimal_play(hand, &paytable);
+        assert_eq!(play.hold, hand.map(Some));
+        assert_eq!(play.expected_return, Rational::from_integer(1));
+        // deuces wild: two deuces make a straight flush with any card, and a
+        // single deuce is worth more kept alone than with either natural
+        let game = Game::deuces_wild();
+        let hand = parse_hand::<3>("2c 2d Kh").unwrap();
+        let play = game.optimal_play(hand, &paytable);
+        assert_eq!(play.expected_return, Rational::from_integer(1));
+        let play = game.optimal_play(parse_hand::<3>("2c 7d Kh").unwrap(), &paytable);
+        assert_eq!(play.hold.iter().flatten().count(), 1);
+        assert!(play.expected_return > Rational::new(1, 10));
+    }
+    #[test]
+    fn shoe_deals() {
+        let game = Game::shoe(2);
+        let table = game.all_deals::<3>();
+        assert_eq!(table.total(), 182104);
+        // suited trips are trips, eight copies of each rank in 13 ranks
+        assert_eq!(table.count(Hand::ThreeOfAKind), Some(13 * 56));
+        assert_eq!(table.count(Hand::StraightFlush), Some(12 * 4 * 8));
+        let paytable = Paytable::new("trips", [1, 1, 0, 0, 0, 0]).unwrap();
+        let hand = game.deck.parse_hand::<3>("As As 2c").unwrap();
+        let play = game.optimal_play(hand, &paytable);
+        // the discarded copy of the ace of spades is gone, six aces remain
+        assert_eq!(play.hold, [Some(hand[0]), Some(hand[1]), None]);
+        assert_eq!(play.expected_return, Rational::new(6, 101));
+        let outcomes = GameOutcomes::<3>::for_game(&game);
+        assert_eq!(outcomes.deals(), 182104);
+    }
+    #[test]
+    fn compositions() {
+        // the short deck runs A-6-7-8-9, six straights in each suit
+        let short = Game::from_deck(Deck::short()).all_deals::<5>();
+        assert_eq!(short.total(), 376992);
+        assert_eq!(short.count(Hand::StraightFlush), Some(24));
+        assert_eq!(short.count(Hand::FourOfAKind), Some(288));
+        assert_eq!(short.count(Hand::FullHouse), Some(1728));
+        assert_eq!(short.count(Hand::Flush), Some(480));
+        assert_eq!(short.count(Hand::Straight), Some(6120));
+        assert_eq!(short.count(Hand::HighCard), Some(122400));
+        // 5-6-7 runs on to J-Q-K and Q-K-A, nine runs of three
+        let spanish = Game::from_deck(Deck::spanish());
+        let table = spanish.all_deals::<3>();
+        assert_eq!(table.total(), 9880);
+        assert_eq!(table.count(Hand::StraightFlush), Some(36));
+        assert_eq!(table.count(Hand::Straight), Some(540));
+        assert_eq!(table.count(Hand::Flush), Some(444));
+        let hand = spanish.deck.parse_hand::<3>("6c 7c Jc").unwrap();
+        assert_eq!(spanish.evaluator.get_hand(&hand), Hand::StraightFlush);
+        // classes of a three suit deck relabel onto its own suits
+        let game = Game::from_deck(Deck::new(
+            Rank::A..=Rank::King,
+            [Suit::Clubs, Suit::Hearts, Suit::Spades],
+        ));
+        let mut from_classes = HandTable::<3>::default();
+        for class in game.deal_classes::<3>() {
+            assert!(game.deck.can_deal(&class.cards));
+            from_classes.push_index(game.evaluator.get_hand(&class.cards).index(3), class.weight);
+        }
+        assert_eq!(from_classes, game.all_deals());
+        assert_eq!(GameOutcomes::<3>::for_game(&game).deals(), 9139);
+    }
+    #[test]
+    fn wild_returns() {
+        let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+        let standard = Game::standard().expected_return(&paytable);
+        assert_eq!(standard, Rational::new(9219467, 2993900));
+        assert!(Game::with_jokers(1).expected_return(&paytable) > standard);
+        assert!(Game::deuces_wild().expected_return(&paytable) > standard);
+    }
+}
@@ -0,0 +1,626 @@
+use super::{Card, Rank, Suit};
+use std::{cmp::Ordering, ops::RangeInclusive};
+
+/// Category of a hand. The first six are the three card categories, best
+/// first, and their discriminants index three card tables and pays. Larger
+/// hands add the categories after them, see [`Hand::categories`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Hand {
+    StraightFlush = 0,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    Pair,
+    HighCard,
+    FourOfAKind,
+    FullHouse,
+    TwoPair,
+}
+impl Hand {
+    /// Every category of every hand size
+    pub const ALL: [Hand; Hand::TwoPair as usize + 1] = [
+        Hand::StraightFlush,
+        Hand::ThreeOfAKind,
+        Hand::Straight,
+        Hand::Flush,
+        Hand::Pair,
+        Hand::HighCard,
+        Hand::FourOfAKind,
+        Hand::FullHouse,
+        Hand::TwoPair,
+    ];
+    /// Hand sizes that have categories
+    pub const SIZES: RangeInclusive<usize> = 3..=5;
+    /// Categories a hand of `size` cards can make, best first. Three and
+    /// five card hands rank the rarer hands higher, so three of a kind beats
+    /// a straight in three cards but not in five. Four card hands follow the
+    /// standard Four Card Poker ranking instead, where a flush beats a
+    /// straight and two pair though both are rarer.
+    ///
+    /// Panics if `size` is not in [`Hand::SIZES`].
+    pub fn categories(size: usize) -> &'static [Hand] {
+        use Hand::*;
+        match size {
+            3 => &[StraightFlush, ThreeOfAKind, Straight, Flush, Pair, HighCard],
+            4 => &[
+                FourOfAKind,
+                StraightFlush,
+                ThreeOfAKind,
+                Flush,
+                Straight,
+                TwoPair,
+                Pair,
+                HighCard,
+            ],
+            5 => &[
+                StraightFlush,
+                FourOfAKind,
+                FullHouse,
+                Flush,
+                Straight,
+                ThreeOfAKind,
+                TwoPair,
+                Pair,
+                HighCard,
+            ],
+            _ => panic!("hands of {} cards have no categories", size),
+        }
+    }
+    /// Position of the category among [`Hand::categories`] of `size`, used
+    /// to index tables and pays
+    pub fn index(&self, size: usize) -> usize {
+        Hand::categories(size)
+            .iter()
+            .position(|hand| hand == self)
+            .unwrap_or_else(|| panic!("{} cards can not make {}", size, self.name()))
+    }
+    /// Name used for the hand in paytable files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hand::StraightFlush => "straight_flush",
+            Hand::ThreeOfAKind => "three_of_a_kind",
+            Hand::Straight => "straight",
+            Hand::Flush => "flush",
+            Hand::Pair => "pair",
+            Hand::HighCard => "high_card",
+            Hand::FourOfAKind => "four_of_a_kind",
+            Hand::FullHouse => "full_house",
+            Hand::TwoPair => "two_pair",
+        }
+    }
+    /// Whether `cards`, with the given [`rank_counts`], hold this category
+    /// regardless of any better one
+    pub(crate) fn matches<const N: usize>(
+        &self,
+        evaluator: &Evaluator,
+        cards: &[Card; N],
+        counts: &[usize],
+    ) -> bool {
+        match self {
+            Hand::StraightFlush => evaluator.is_sequence(cards) && is_same_suit(cards),
+            Hand::FourOfAKind => counts[0] >= 4,
+            Hand::FullHouse => counts[0] >= 3 && counts[1] >= 2,
+            Hand::Flush => is_same_suit(cards),
+            Hand::Straight => evaluator.is_sequence(cards),
+            Hand::ThreeOfAKind => counts[0] >= 3,
+            Hand::TwoPair => counts[0] >= 2 && counts[1] >= 2,
+            Hand::Pair => counts[0] >= 2,
+            Hand::HighCard => true,
+        }
+    }
+}
+impl std::str::FromStr for Hand {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Hand::ALL
+            .iter()
+            .find(|hand| hand.name() == s)
+            .copied()
+            .ok_or_else(|| s.to_string())
+    }
+}
+/// Number of cards of each rank that appears, most first
+pub(crate) fn rank_counts<const N: usize>(cards: &[Card; N]) -> [usize; 15] {
+    let mut counts = [0; 15];
+    for card in cards {
+        counts[card.rank as usize] += 1;
+    }
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts
+}
+/// Value of a rank when aces play high
+fn ace_high(rank: Rank) -> u8 {
+    match rank {
+        Rank::A => 14,
+        rank => rank as u8,
+    }
+}
+/// Checks if the ranks form a run, aces count both low (A-2-3) and high (Q-K-A)
+pub fn is_sequence<const N: usize>(cards: &[Card; N]) -> bool {
+    Evaluator::default().is_sequence(cards)
+}
+/// Whether positions follow one another once sorted
+fn is_run<const N: usize>(mut positions: [u32; N]) -> bool {
+    positions.sort_unstable();
+    positions.windows(2).all(|pair| pair[0] + 1 == pair[1])
+}
+pub fn is_same_suit<const N: usize>(cards: &[Card; N]) -> bool {
+    cards.iter().all(|card| card.suit == cards[0].suit)
+}
+pub fn is_same_rank<const N: usize>(cards: &[Card; N]) -> bool {
+    cards.iter().all(|card| card.rank == cards[0].rank)
+}
+pub fn is_two_same_rank<const N: usize>(cards: &[Card; N]) -> bool {
+    rank_counts(cards)[0] >= 2
+}
+/// Classifies cards into the best of their hand size's categories, jokers
+/// play as whatever card makes the best hand
+pub fn get_hand<const N: usize>(cards: &[Card; N]) -> Hand {
+    Evaluator::default().get_hand(cards)
+}
+/// Where an ace may sit in a straight
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StraightRule {
+    /// Q-K-A runs, A-2-3 does not
+    AceHigh,
+    /// A-2-3 runs, Q-K-A does not
+    AceLow,
+    /// both A-2-3 and Q-K-A run
+    #[default]
+    AceHighOrLow,
+    /// runs may also wrap round from king to ace, as K-A-2
+    Wraparound,
+}
+/// Evaluates hands that may hold wild cards. Jokers are always wild and
+/// `wild_rank` makes every card of one rank wild as well. Each wild card
+/// plays as whichever card not already in the hand makes the best hand, so
+/// four aces and a joker are four of a kind, there is no five of a kind.
+///
+/// Straights run through the ranks in play, so a deck with ranks removed
+/// skips over them: the 36 card short deck runs A-6-7-8-9. Where the ace
+/// sits is up to the [`StraightRule`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Evaluator {
+    /// rank that plays wild along with the jokers, twos in deuces wild
+    pub wild_rank: Option<Rank>,
+    /// where aces may sit in a straight
+    pub straights: StraightRule,
+    /// ranks in play, bit `rank as usize` set for each
+    ranks: u16,
+}
+impl Default for Evaluator {
+    /// No wild rank and all thirteen ranks in play
+    fn default() -> Self {
+        Self {
+            wild_rank: None,
+            straights: StraightRule::default(),
+            ranks: 0,
+        }
+        .with_ranks(Rank::A..=Rank::King)
+    }
+}
+impl Evaluator {
+    /// Every two is wild
+    pub fn deuces_wild() -> Self {
+        Self {
+            wild_rank: Some(Rank::Two),
+            ..Self::default()
+        }
+    }
+    /// Plays with only `ranks`, as dealt from a deck with the others removed
+    pub fn with_ranks(self, ranks: impl IntoIterator<Item = Rank>) -> Self {
+        Self {
+            ranks: ranks
+                .into_iter()
+                .filter(|rank| *rank != Rank::Joker)
+                .fold(0, |mask, rank| mask | 1 << rank as usize),
+            ..self
+        }
+    }
+    /// Ranks in play, lowest first
+    pub fn ranks(&self) -> Vec<Rank> {
+        (Rank::A..=Rank::King)
+            .filter(|rank| self.ranks & (1 << *rank as usize) != 0)
+            .collect()
+    }
+    /// Checks if the ranks form a run through the ranks in play, with aces
+    /// placed as the [`StraightRule`] allows
+    pub fn is_sequence<const N: usize>(&self, cards: &[Card; N]) -> bool {
+        let low = || is_run(cards.map(|card| self.position(card.rank)));
+        let high = || is_run(cards.map(|card| self.position_ace_high(card.rank)));
+        match self.straights {
+            StraightRule::AceHigh => high(),
+            StraightRule::AceLow => low(),
+            StraightRule::AceHighOrLow => low() || high(),
+            StraightRule::Wraparound => self.wrap_start(&self.sorted_positions(cards)).is_some(),
+        }
+    }
+    /// Positions of `cards` with aces low, sorted
+    fn sorted_positions<const N: usize>(&self, cards: &[Card; N]) -> [u32; N] {
+        let mut positions = cards.map(|card| self.position(card.rank));
+        positions.sort_unstable();
+        positions
+    }
+    /// Where a run that may wrap from king to ace starts among sorted
+    /// `positions`, or `None` when they do not run
+    fn wrap_start<const N: usize>(&self, positions: &[u32; N]) -> Option<usize> {
+        // the gap from each card up to the next, the last one wrapping round
+        // to the first, a run has at most one gap other than one
+        let gap = |i: usize| match i + 1 {
+            next if next < N => positions[next] - positions[i],
+            _ => positions[0] + self.ranks.count_ones() - positions[i],
+        };
+        let wide = (0..N).filter(|i| gap(*i) != 1).collect::<Vec<_>>();
+        match wide[..] {
+            [] => Some(0),
+            [i] if gap(i) > 1 => Some((i + 1) % N),
+            _ => None,
+        }
+    }
+    /// Fewest ranks missing from a run of `size` cards through the distinct
+    /// ranks of `cards`, `None` when no run holds them all
+    pub(crate) fn straight_gaps(&self, cards: &[Card], size: usize) -> Option<u32> {
+        let count = self.ranks.count_ones();
+        let low = cards
+            .iter()
+            .map(|card| self.position(card.rank))
+            .collect::<Vec<_>>();
+        let high = cards
+            .iter()
+            .map(|card| self.position_ace_high(card.rank))
+            .collect::<Vec<_>>();
+        let placements = match self.straights {
+            StraightRule::AceHigh => vec![high],
+            StraightRule::AceLow => vec![low],
+            StraightRule::AceHighOrLow => vec![low, high],
+            StraightRule::Wraparound => (0..count)
+                .map(|shift| low.iter().map(|p| (p + shift) % count).collect())
+                .collect(),
+        };
+        placements
+            .iter()
+            .filter_map(|positions| {
+                let span = positions.iter().max()? - positions.iter().min()? + 1;
+                (span as usize <= size).then(|| span - positions.len() as u32)
+            })
+            .min()
+    }
+    /// Position of `rank` among the ranks in play with aces low
+    fn position(&self, rank: Rank) -> u32 {
+        (self.ranks & ((1 << rank as usize) - 1)).count_ones()
+    }
+    /// Position of `rank` among the ranks in play with aces high
+    fn position_ace_high(&self, rank: Rank) -> u32 {
+        match rank {
+            Rank::A => self.ranks.count_ones(),
+            rank => self.position(rank),
+        }
+    }
+    /// Category of a hand without wild cards
+    fn natural_hand<const N: usize>(&self, cards: &[Card; N]) -> Hand {
+        let counts = rank_counts(cards);
+        *Hand::categories(N)
+            .iter()
+            .find(|hand| hand.matches(self, cards, &counts))
+            .expect("every hand is at least high card")
+    }
+    /// Strength of a hand without wild cards
+    fn natural_rank<const N: usize>(&self, cards: &[Card; N]) -> HandRank<N> {
+        let hand = self.natural_hand(cards);
+        if self.is_sequence(cards) {
+            return HandRank {
+                hand,
+                kickers: self.straight_kickers(cards),
+            };
+        }
+        let values = cards.map(|card| ace_high(card.rank));
+        let count = |value: &u8| values.iter().filter(|v| *v == value).count();
+        let mut kickers = values;
+        kickers.sort_unstable_by(|a, b| count(b).cmp(&count(a)).then(b.cmp(a)));
+        HandRank { hand, kickers }
+    }
+    /// Ranks of a straight from its top card down. An ace plays high when
+    /// the rule lets it, otherwise low, and a run wrapping from king to ace
+    /// is topped by its last card after the wrap, so K-A-2 ranks below
+    /// A-2-3.
+    fn straight_kickers<const N: usize>(&self, cards: &[Card; N]) -> [u8; N] {
+        let descending = |mut values: [u8; N]| {
+            values.sort_unstable_by(|a, b| b.cmp(a));
+            values
+        };
+        let high = is_run(cards.map(|card| self.position_ace_high(card.rank)));
+        if high && self.straights != StraightRule::AceLow {
+            return descending(cards.map(|card| ace_high(card.rank)));
+        }
+        let positions = self.sorted_positions(cards);
+        let start = match self.straights {
+            StraightRule::Wraparound => self.wrap_start(&positions).unwrap_or(0),
+            _ => 0,
+        };
+        let mut sorted = cards.map(|card| card.rank as u8);
+        sorted.sort_unstable();
+        // from the top of the run down, wrapping back past the start
+        std::array::from_fn(|i| sorted[(start + N - 1 - i) % N])
+    }
+    pub fn is_wild(&self, card: &Card) -> bool {
+        card.rank == Rank::Joker || Some(card.rank) == self.wild_rank
+    }
+    /// Best category `cards` can make
+    pub fn get_hand<const N: usize>(&self, cards: &[Card; N]) -> Hand {
+        let mut best = Hand::HighCard;
+        self.for_each_resolution(cards, |hand| {
+            let hand = self.natural_hand(hand);
+            if hand.index(N) < best.index(N) {
+                best = hand;
+            }
+        });
+        best
+    }
+    /// Best showdown strength `cards` can make
+    pub fn hand_rank<const N: usize>(&self, cards: &[Card; N]) -> HandRank<N> {
+        let mut best = None;
+        self.for_each_resolution(cards, |hand| {
+            best = best.max(Some(self.natural_rank(hand)));
+        });
+        best.expect("every hand resolves at least once")
+    }
+    /// Calls `f` with every natural hand the wild cards of `cards` can stand
+    /// for, or with `cards` itself when none are wild
+    pub(crate) fn for_each_resolution<const N: usize>(
+        &self,
+        cards: &[Card; N],
+        mut f: impl FnMut(&[Card; N]),
+    ) {
+        let wild = (0..N)
+            .filter(|i| self.is_wild(&cards[*i]))
+            .collect::<Vec<_>>();
+        if wild.is_empty() {
+            return f(cards);
+        }
+        let naturals = cards
+            .iter()
+            .filter(|card| !self.is_wild(card))
+            .copied()
+            .collect::<Vec<_>>();
+        // suits only matter for flushes, so a wild card takes the suit of the
+        // naturals when they share one and any unused suit otherwise
+        let flush = match naturals.first() {
+            Some(first) if naturals.iter().all(|card| card.suit == first.suit) => first.suit,
+            _ => Suit::Clubs,
+        };
+        let ranks = self.ranks();
+        // wild cards are interchangeable, so they take every multiset of
+        // ranks as non decreasing picks
+        let mut picked = vec![0; wild.len()];
+        loop {
+            let mut hand = *cards;
+            let mut used = naturals.clone();
+            for (position, pick) in wild.iter().zip(picked.iter()) {
+                let rank = ranks[*pick];
+                let suit = std::iter::once(flush)
+                    .chain(Suit::ALL)
+                    .find(|suit| !used.contains(&Card { rank, suit: *suit }))
+                    .unwrap_or(flush);
+                hand[*position] = Card { rank, suit };
+                used.push(hand[*position]);
+            }
+            f(&hand);
+            let Some(i) = (0..picked.len())
+                .rev()
+                .find(|i| picked[*i] + 1 < ranks.len())
+            else {
+                return;
+            };
+            picked[i] += 1;
+            for j in i + 1..picked.len() {
+                picked[j] = picked[i];
+            }
+        }
+    }
+}
+/// Complete strength of a hand, ordered by category first and then by the
+/// tie breaking ranks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HandRank<const N: usize = 3> {
+    pub hand: Hand,
+    /// ranks that break ties within the category, most significant first,
+    /// as 2 through 14 with aces high. Ranks held more often come first, so a
+    /// pair lists the paired rank before the kickers, and a straight lists
+    /// its cards from the top, so A-2-3 is `[3, 2, 1]` and ranks below 2-3-4.
+    pub kickers: [u8; N],
+}
+impl<const N: usize> Ord for HandRank<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.hand == other.hand {
+            return self.kickers.cmp(&other.kickers);
+        }
+        other.hand.index(N).cmp(&self.hand.index(N))
+    }
+}
+impl<const N: usize> PartialOrd for HandRank<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+/// Ranks a hand for a showdown, jokers play as whatever card makes the best
+/// hand
+pub fn hand_rank<const N: usize>(cards: &[Card; N]) -> HandRank<N> {
+    Evaluator::default().hand_rank(cards)
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{deal_classes, generate_deck, parse_hand};
+    #[test]
+    fn sequence() {
+        assert!(is_sequence(&parse_hand::<3>("Ac 2c 3c").unwrap()));
+        assert!(is_sequence(&parse_hand::<3>("Qc Kc Ac").unwrap()));
+        assert!(!is_sequence(&parse_hand::<3>("Kc Ac 2c").unwrap()));
+        assert!(!is_sequence(&parse_hand::<3>("Ac 2c 4c").unwrap()));
+        assert!(is_sequence(&parse_hand::<5>("Th Jc Qc Kd Ac").unwrap()));
+        assert!(!is_sequence(&parse_hand::<4>("2h 3c 3d 4c").unwrap()));
+    }
+    /// Straightforward strength written independently of `hand_rank`, ranks
+    /// the category from scratch and then lists the ranks to compare
+    fn brute_force_strength(cards: &[Card; 3]) -> (u8, Vec<u8>) {
+        fn value(rank: Rank) -> u8 {
+            if rank == Rank::A {
+                14
+            } else {
+                rank as u8
+            }
+        }
+        fn straight_top(cards: &[Card; 3]) -> Option<u8> {
+            // every run of three, A-2-3 topped by the three up to Q-K-A
+            (3..=14u8).find(|top| {
+                let run = [*top, top - 1, top - 2].map(|r| if r == 1 { 14 } else { r });
+                let mut ranks = cards.map(|card| value(card.rank));
+                ranks.sort_unstable();
+                let mut expected = run;
+                expected.sort_unstable();
+                ranks == expected
+            })
+        }
+        fn strength(cards: &[Card; 3]) -> (u8, Vec<u8>) {
+            let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+            let mut ranks = cards
+                .iter()
+                .map(|card| value(card.rank))
+                .collect::<Vec<_>>();
+            ranks.sort_unstable_by(|a, b| b.cmp(a));
+            let count = |rank: u8| ranks.iter().filter(|r| **r == rank).count();
+            match straight_top(cards) {
+                Some(top) if flush => (5, vec![top]),
+                _ if count(ranks[0]) == 3 => (4, vec![ranks[0]]),
+                Some(top) => (3, vec![top]),
+                None if flush => (2, ranks),
+                None => match ranks.iter().find(|rank| count(**rank) == 2) {
+                    Some(pair) => (
+                        1,
+                        vec![*pair, *ranks.iter().find(|r| count(**r) == 1).unwrap()],
+                    ),
+                    None => (0, ranks),
+                },
+            }
+        }
+        strength(cards)
+    }
+    #[test]
+    fn hand_rank_matches_brute_force() {
+        let hands = deal_classes::<3>()
+            .into_iter()
+            .map(|class| class.cards)
+            .collect::<Vec<_>>();
+        let ranks = hands.iter().map(hand_rank).collect::<Vec<_>>();
+        let expected = hands.iter().map(brute_force_strength).collect::<Vec<_>>();
+        for a in 0..hands.len() {
+            for b in 0..hands.len() {
+                assert_eq!(
+                    ranks[a].cmp(&ranks[b]),
+                    expected[a].cmp(&expected[b]),
+                    "{:?} {:?}",
+                    hands[a],
+                    hands[b]
+                );
+            }
+        }
+    }
+    /// Best strength of `cards` with the wild cards from `from` on replaced by
+    /// every combination of natural cards that keeps the hand distinct
+    fn brute_force_wild(evaluator: &Evaluator, cards: [Card; 3], from: usize) -> HandRank {
+        let Some(wild) = (from..3).find(|i| evaluator.is_wild(&cards[*i])) else {
+            return evaluator.natural_rank(&cards);
+        };
+        let later_wild = |i: usize| i > wild && evaluator.is_wild(&cards[i]);
+        generate_deck()
+            .into_iter()
+            .filter(|card| (0..3).all(|i| i == wild || later_wild(i) || cards[i] != *card))
+            .map(|card| {
+                let mut hand = cards;
+                hand[wild] = card;
+                brute_force_wild(evaluator, hand, wild + 1)
+            })
+            .max()
+            .expect("a wild card has substitutes")
+    }
+    #[test]
+    fn wilds_match_brute_force() {
+        let copies = crate::Deck::standard().with_jokers(2).copies();
+        for evaluator in [Evaluator::default(), Evaluator::deuces_wild()] {
+            crate::deal::for_each_dealt_hand(&copies, |cards: [Card; 3], _| {
+                if cards.iter().any(|card| evaluator.is_wild(card)) {
+                    let expected = brute_force_wild(&evaluator, cards, 0);
+                    assert_eq!(evaluator.hand_rank(&cards), expected, "{:?}", cards);
+                    assert_eq!(evaluator.get_hand(&cards), expected.hand, "{:?}", cards);
+                }
+            });
+        }
+        let rank = |s| hand_rank(&parse_hand::<3>(s).unwrap());
+        assert_eq!(rank("Xc Ah Kh").kickers, [14, 13, 12]);
+        assert_eq!(rank("Xc Ac Ad").hand, Hand::ThreeOfAKind);
+        assert_eq!(rank("Xc Ac Kd").hand, Hand::Straight);
+        assert_eq!(rank("Xc Xh 5d").hand, Hand::StraightFlush);
+        let deuces = Evaluator::deuces_wild();
+        assert_eq!(
+            deuces.get_hand(&parse_hand::<5>("2c 2d Ah Ad 9s").unwrap()),
+            Hand::FourOfAKind
+        );
+    }
+    #[test]
+    fn straight_rules() {
+        let runs = |straights| {
+            let evaluator = Evaluator {
+                straights,
+                ..Evaluator::default()
+            };
+            let game = crate::Game {
+                evaluator,
+                ..crate::Game::standard()
+            };
+            let table = game.all_deals::<3>();
+            let count = |hand| table.count(hand).unwrap();
+            assert_eq!(count(Hand::Straight), count(Hand::StraightFlush) / 4 * 60);
+            count(Hand::StraightFlush) / 4
+        };
+        assert_eq!(runs(StraightRule::AceHighOrLow), 12);
+        assert_eq!(runs(StraightRule::AceHigh), 11);
+        assert_eq!(runs(StraightRule::AceLow), 11);
+        assert_eq!(runs(StraightRule::Wraparound), 13);
+        let wrap = Evaluator {
+            straights: StraightRule::Wraparound,
+            ..Evaluator::default()
+        };
+        let rank = |s| wrap.hand_rank(&parse_hand::<3>(s).unwrap());
+        assert_eq!(rank("Kc Ad 2h").hand, Hand::Straight);
+        assert!(rank("Kc Ad 2h") < rank("Ac 2d 3h"));
+        assert!(rank("Qc Kd Ah") > rank("Jc Qd Kh"));
+        assert_eq!(rank("Qc Kd Ah").kickers, [14, 13, 12]);
+        assert!(!wrap.is_sequence(&parse_hand::<5>("Jc Qd Kh Ac 3c").unwrap()));
+        assert!(wrap.is_sequence(&parse_hand::<5>("Jc Qd Kh Ac 2c").unwrap()));
+        let low = Evaluator {
+            straights: StraightRule::AceLow,
+            ..Evaluator::default()
+        };
+        let hand = parse_hand::<3>("Qc Kc Ac").unwrap();
+        assert_eq!(low.get_hand(&hand), Hand::Flush);
+        assert_eq!(low.hand_rank(&hand).kickers, [14, 13, 12]);
+        // the solver breaks up a suited Q-K-A that is no straight flush
+        let game = crate::Game {
+            evaluator: low,
+            ..crate::Game::standard()
+        };
+        let paytable = crate::Paytable::new("straight flush", [1, 0, 0, 0, 0, 0]).unwrap();
+        let play = game.optimal_play(hand, &paytable);
+        assert_ne!(play.hold, hand.map(Some));
+        assert!(play.expected_return < crate::Rational::new(1, 10));
+    }
+    #[test]
+    fn ace_straights() {
+        let rank = |s| hand_rank(&parse_hand::<3>(s).unwrap());
+        assert!(rank("Ac Kc Qc") > rank("Kd Qd Jd"));
+        assert!(rank("2h 3h 4h") > rank("Ah 2h 3h"));
+        assert!(rank("Ac 2d 3h") < rank("2c 3d 4h"));
+        assert!(rank("Ac 2d 3h") > rank("Ac Kd Jd"));
+        assert_eq!(rank("Ac Kd 9h"), rank("As Kh 9c"));
+    }
+}
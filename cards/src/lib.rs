@@ -0,0 +1,84 @@
+//! Analysis of three card draw games.
+//!
+//! A hand is dealt three cards from a standard deck, the player may discard
+//! any of them and draw replacements, and the final hand is paid from a
+//! [`Paytable`] loaded from TOML or JSON. Hands are `[Card; N]`, with three
+//! cards the default, and four and five card hands adding the categories of
+//! [`Hand::categories`]. The crate covers
+//!
+//! * deck generation: [`Card`], [`Rank`], [`Suit`], [`generate_deck`] and the
+//!   suit relabeling classes from [`deal_classes`], with cards written in the
+//!   usual notation, `parse_hand("8c 9c Tc")`, and a [`ShuffledDeck`] dealing
+//!   and burning cards from a seeded shuffle that replays on every platform
+//! * evaluation: [`get_hand`] and the [`Hand`] categories, with [`hand_rank`]
+//!   breaking ties for showdowns, and an [`Evaluator`] resolving wild cards
+//! * custom categories: [`Categories`] of [`Category`] predicates ranked by
+//!   priority, such as a suited A-K-Q "Mini Royal" above straight flushes,
+//!   replace the standard ones in a [`Game`] and size its tables and pays
+//! * other decks: a [`Game`] pairs a [`Deck`], a multi deck shoe, a short or
+//!   stripped deck or one with jokers, with an evaluator resolving wild
+//!   cards and running straights through the ranks in play under a
+//!   [`StraightRule`], such as
+//!   [`Game::deuces_wild`], and solves it like the standard game
+//! * strategy: [`optimal_play`] prices every hold for a dealt hand,
+//!   [`hold_cost`] what a mistaken hold gives up against it, a
+//!   [`StrategyChart`] ranks the holds into rules a player can follow, and
+//!   [`GameOutcomes`] keeps the draw counts of every deal for reuse
+//! * paytable math: [`Paytable`] files, [`HandTable`] counts and
+//!   [`calculate_expected_return`], with the variance and hit frequency in
+//!   [`GameStatistics`], all exact [`Rational`] values
+//! * playing: a [`Session`] deals from a [`ShuffledDeck`], keeps the credit
+//!   balance and prices the player's hold against the optimal one, and a
+//!   [`Simulation`] plays seeded sessions of them under a [`HoldStrategy`]
+//!   to report the risk of ruin and the spread of final bankrolls
+//! * head to head play: [`analyze_ante_play`] solves the Ante/Play game
+//!   against a dealer who qualifies on Queen high, and [`PairPlus`] prices
+//!   the side bet on the player's hand alone
+//! * video poker: [`VideoPoker`] solves five card [`JacksOrBetter`] exactly,
+//!   every hold of every deal
+#![feature(step_trait)]
+mod ante_play;
+mod card;
+mod category;
+mod chart;
+mod deal;
+mod deck;
+mod game;
+mod hand;
+mod outcomes;
+mod pair_plus;
+mod paytable;
+mod rank;
+mod rational;
+mod rng;
+mod session;
+mod simulate;
+mod stats;
+mod strategy;
+mod suit;
+mod table;
+mod video_poker;
+pub use ante_play::{analyze_ante_play, AntePlayAnalysis, AntePlayDecision, AntePlayRules};
+pub use card::{generate_deck, parse_cards, parse_hand, Card, Cards, ParseCardError};
+pub use category::{Categories, Category};
+pub use chart::{ChartRule, StrategyChart};
+pub use deal::{deal_classes, DealClass};
+pub use deck::{Deck, ShuffledDeck};
+pub use game::Game;
+pub use hand::{
+    get_hand, hand_rank, is_same_rank, is_same_suit, is_sequence, is_two_same_rank, Evaluator,
+    Hand, HandRank, StraightRule,
+};
+pub use outcomes::{ClassOutcomes, GameOutcomes};
+pub use pair_plus::PairPlus;
+pub use paytable::{Paytable, PaytableError};
+pub use rank::Rank;
+pub use rational::{parse_decimal, to_f64, Rational};
+pub use rng::Rng;
+pub use session::{Round, Session, SessionError};
+pub use simulate::{Bin, HoldStrategy, SessionResult, Simulation, SimulationReport};
+pub use stats::{inverse_normal, GameStatistics};
+pub use strategy::{calculate_expected_return, hold_cost, optimal_play, HoldAdvice, Play};
+pub use suit::Suit;
+pub use table::{HandPayGuess, HandTable};
+pub use video_poker::{JacksOrBetter, VideoPoker};
@@ -1,363 +1,1178 @@
-#![feature(step_trait)]
-mod rank;
-mod suit;
-pub use rank::Rank;
-use std::cmp::Ordering;
-pub use suit::Suit;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use cs411_cards::{
+    analyze_ante_play, parse_decimal, to_f64, AntePlayRules, Card, Deck, Game, GameOutcomes, Hand,
+    HandPayGuess, HoldStrategy, JacksOrBetter, PairPlus, Paytable, Rank, Rational, Session,
+    Simulation, StraightRule, StrategyChart, VideoPoker,
+};
+use serde::Serialize;
+use std::{
+    fmt,
+    io::{BufRead, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub enum Hand {
-    StraightFlush = 0,
-    ThreeOfAKind,
-    Straight,
-    Flush,
-    Pair,
-    HighCard,
-}
-impl From<usize> for Hand {
-    fn from(i: usize) -> Self {
-        match i {
-            0 => Hand::StraightFlush,
-            1 => Hand::ThreeOfAKind,
-            2 => Hand::Straight,
-            3 => Hand::Flush,
-            4 => Hand::Pair,
-            5 => Hand::HighCard,
-            _ => panic!("invalid hand: {}", i),
+/// Analyzer for three card draw games
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// how results are printed
+    #[arg(long, value_enum, default_value_t = Format::Human, global = true)]
+    format: Format,
+    /// print suits as glyphs, e.g. T♣
+    #[arg(long, global = true)]
+    glyphs: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+}
+#[derive(Clone, Copy, ValueEnum)]
+enum SearchMode {
+    /// return when every deal is drawn to optimally, lists every candidate
+    Optimal,
+    /// return of the dealt hand without drawing, stops at the first candidate
+    Dealt,
+}
+#[derive(Clone, Copy, ValueEnum)]
+enum Strategy {
+    /// the hold with the best expected return
+    Optimal,
+    /// stand pat on every dealt hand
+    HoldEverything,
+}
+#[derive(Clone, Copy, ValueEnum)]
+enum DeckKind {
+    /// 52 cards
+    Standard,
+    /// 36 cards, six through ace
+    Short,
+    /// 40 cards, no eights, nines or tens
+    Spanish,
+}
+#[derive(Clone, Copy, ValueEnum)]
+enum Straights {
+    /// Q-K-A runs, A-2-3 does not
+    AceHigh,
+    /// A-2-3 runs, Q-K-A does not
+    AceLow,
+    /// both A-2-3 and Q-K-A run
+    AceHighOrLow,
+    /// runs also wrap from king to ace, as K-A-2
+    Wraparound,
+}
+/// Deck, wild cards and straights of the three card game
+#[derive(Args)]
+struct GameArgs {
+    /// cards in each deck
+    #[arg(long, value_enum, default_value_t = DeckKind::Standard)]
+    deck: DeckKind,
+    /// ranks stripped from the deck, e.g. 2,3
+    #[arg(long, value_delimiter = ',')]
+    strip: Vec<Rank>,
+    /// decks dealt together in a shoe
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    decks: u16,
+    /// wild jokers added to the shoe, written Xc, Xh, Xs and Xd
+    #[arg(long, default_value_t = 0)]
+    jokers: u8,
+    /// rank whose cards are all wild, e.g. 2 for deuces wild
+    #[arg(long)]
+    wild: Option<Rank>,
+    /// where an ace may sit in a straight
+    #[arg(long, value_enum, default_value_t = Straights::AceHighOrLow)]
+    straights: Straights,
+}
+impl GameArgs {
+    /// Exits unless the deck can deal a hand and replace all of it
+    fn game(&self) -> Game {
+        let deck = match self.deck {
+            DeckKind::Standard => Deck::standard(),
+            DeckKind::Short => Deck::short(),
+            DeckKind::Spanish => Deck::spanish(),
+        }
+        .without_ranks(&self.strip);
+        let shoe = deck.cards().repeat(self.decks as usize);
+        let mut game = Game::from_deck(Deck::from_cards(shoe).with_jokers(self.jokers as usize));
+        game.evaluator.wild_rank = self.wild;
+        game.evaluator.straights = match self.straights {
+            Straights::AceHigh => StraightRule::AceHigh,
+            Straights::AceLow => StraightRule::AceLow,
+            Straights::AceHighOrLow => StraightRule::AceHighOrLow,
+            Straights::Wraparound => StraightRule::Wraparound,
+        };
+        if game.deck.len() < 6 {
+            Cli::command()
+                .error(
+                    clap::error::ErrorKind::ValueValidation,
+                    format!(
+                        "a deck of {} cards can not deal three cards and replace them",
+                        game.deck.len()
+                    ),
+                )
+                .exit()
         }
+        game
     }
 }
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some((*other as usize).cmp(&(*self as usize)))
-    }
+#[derive(Subcommand)]
+enum Command {
+    /// Expected return of each paytable when every deal is played optimally
+    Evaluate {
+        /// paytable file or pays in hand order, e.g. 100,99,9,5,0,0
+        #[arg(long, required = true, value_parser = parse_paytable)]
+        paytable: Vec<Paytable>,
+        /// confidence levels to report the volatility index at
+        #[arg(long, default_values_t = [0.9, 0.95], value_parser = parse_confidence)]
+        confidence: Vec<f64>,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Hand category of three cards, e.g. 8c 9c Tc
+    Classify {
+        #[arg(num_args = 1..=3, required = true)]
+        cards: Vec<String>,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Best hold for a dealt hand and the expected return of every hold
+    Advise {
+        #[arg(num_args = 1..=3, required = true)]
+        cards: Vec<String>,
+        /// paytable file or pays in hand order, e.g. 100,99,9,5,0,0
+        #[arg(long, value_parser = parse_paytable)]
+        paytable: Paytable,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// What a hold of a dealt hand gives up against the optimal hold, with
+    /// every hold ranked
+    Cost {
+        #[arg(num_args = 1..=3, required = true)]
+        cards: Vec<String>,
+        /// cards kept, e.g. "8c 9c", every card is drawn when left out
+        #[arg(long, default_value = "")]
+        hold: String,
+        /// paytable file or pays in hand order, e.g. 100,99,9,5,0,0
+        #[arg(long, value_parser = parse_paytable)]
+        paytable: Paytable,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Ranked hold rules read off the optimal strategy, and the return they
+    /// give up against perfect play
+    Chart {
+        /// paytable file or pays in hand order, e.g. 100,99,9,5,0,0
+        #[arg(long, value_parser = parse_paytable)]
+        paytable: Paytable,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Plays the game in the terminal, betting from a balance of credits
+    Play {
+        /// paytable file or pays in hand order, e.g. 100,99,9,5,0,0
+        #[arg(long, value_parser = parse_paytable)]
+        paytable: Paytable,
+        /// credits to start with
+        #[arg(long, default_value_t = 100)]
+        credits: usize,
+        /// seed of the shuffle, replays the same deals, a new one each run
+        /// when left out
+        #[arg(long)]
+        seed: Option<u64>,
+        /// after each hold, show the optimal hold and what the choice costs
+        #[arg(long)]
+        advisor: bool,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Monte Carlo sessions from a starting bankroll: the risk of ruin, the
+    /// hands to bust or double and the spread of final bankrolls
+    Simulate {
+        /// paytable file or pays in hand order, e.g. 100,99,9,5,0,0
+        #[arg(long, value_parser = parse_paytable)]
+        paytable: Paytable,
+        /// credits each session starts with
+        #[arg(long, default_value_t = 100)]
+        bankroll: usize,
+        /// paytable bets staked on each hand
+        #[arg(long, default_value_t = 1)]
+        coins: usize,
+        /// most hands played in a session
+        #[arg(long, default_value_t = 1000)]
+        hands: usize,
+        /// sessions played, each from the starting bankroll
+        #[arg(long, default_value_t = 1000)]
+        sessions: usize,
+        /// seed of the shuffles, the same seed plays the same sessions
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// how the cards to hold are chosen
+        #[arg(long, value_enum, default_value_t = Strategy::Optimal)]
+        strategy: Strategy,
+        /// ranges of final bankroll counted
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        bins: u64,
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// Number of dealt hands in each category
+    Table {
+        #[command(flatten)]
+        game: GameArgs,
+    },
+    /// House edge and raise threshold of the Ante/Play game against a dealer
+    AntePlay {
+        /// ante bonus to one in hand order
+        #[arg(long, default_value = "5,4,1,0,0,0", value_delimiter = ',')]
+        ante_bonus: Vec<usize>,
+        /// pays to one on a winning play bet
+        #[arg(long, default_value_t = 1)]
+        play_win: usize,
+    },
+    /// Return, variance and hit frequency of the Pair Plus side bet
+    PairPlus {
+        /// pays to one in hand order, the common paytables when left out
+        #[arg(long, value_delimiter = ',')]
+        pays: Option<Vec<usize>>,
+    },
+    /// Jacks or Better: the return of the paytable over every deal, or the
+    /// best hold for five dealt cards
+    JacksOrBetter {
+        #[arg(num_args = 0..=5)]
+        cards: Vec<String>,
+        /// paytable file or pays for one credit, royal flush first, e.g.
+        /// paytables/nine_six.toml or 800,50,25,9,6,4,3,2,1,0,0
+        #[arg(long, default_value = "800,50,25,9,6,4,3,2,1,0,0", value_parser = parse_poker_pays)]
+        pays: JacksOrBetter,
+    },
+    /// Searches for descending pays whose return falls in a range
+    SearchPaytable {
+        /// range of acceptable returns, e.g. 0.99..1.00
+        #[arg(long, value_parser = parse_range)]
+        target_rtp: (Rational, Rational),
+        /// how the return of a candidate is measured
+        #[arg(long, value_enum, default_value_t = SearchMode::Optimal)]
+        mode: SearchMode,
+        /// starting pays in hand order, `_` marks a pay to search for
+        #[arg(long, default_value = "40,_,_,_,_,0", value_parser = parse_guess)]
+        pays: HandPayGuess,
+        #[command(flatten)]
+        game: GameArgs,
+    },
 }
-/// calculates optimal play based on card
-fn optimal_play(
-    cards: [Card; 3],
-    paytable: &[usize; Hand::HighCard as usize + 1],
-) -> ([Option<Card>; 3], f32) {
-    let deck = generate_deck();
-    let mut switch_tables = vec![];
-    for i in 0..cards.len() {
-        switch_tables.push(HandTable::default());
-        for card in deck.iter() {
-            if !cards.contains(card) {
-                let mut new_hand = cards.clone();
-                new_hand[i] = *card;
-                switch_tables[i].push_sequence(new_hand);
-            }
-        }
+/// Reads a paytable file, or a comma separated list of pays in hand order
+fn parse_paytable(s: &str) -> Result<Paytable, String> {
+    let paytable = if s.contains(',') {
+        Paytable::new(s, parse_pays(s)?)
+    } else {
+        Paytable::load(s)
     }
-    let mut max_return = 0.0f32;
-    let mut max_idx = 0;
-    for i in 0..switch_tables.len() {
-        let pay = switch_tables[i].calcualte_return_full(&paytable);
-        if pay > max_return {
-            max_return = pay;
-            max_idx = i;
-        }
+    .map_err(|e| e.to_string())?;
+    if paytable.cards != 3 {
+        return Err(format!("{} is not a three card paytable", paytable.name));
+    }
+    if !paytable.is_standard() {
+        return Err(format!("{} pays custom categories", paytable.name));
     }
-    let current_hand = get_hand(&cards);
-    let current_return = paytable[current_hand as usize] as f32;
-    if max_return > current_return {
-        let mut out = [Some(cards[0]), Some(cards[1]), Some(cards[2])];
-        out[max_idx] = None;
-        (out, max_return)
+    Ok(paytable)
+}
+/// Reads a comma separated list of pays in hand order
+fn parse_pays(s: &str) -> Result<Vec<usize>, String> {
+    s.split(',')
+        .map(|pay| pay.trim().parse::<usize>().map_err(|e| e.to_string()))
+        .collect()
+}
+/// Reads a Jacks or Better paytable file, or a comma separated list of its
+/// pays, royal flush first
+fn parse_poker_pays(s: &str) -> Result<JacksOrBetter, String> {
+    if s.contains(',') {
+        JacksOrBetter::new(s, parse_pays(s)?)
     } else {
-        (
-            [Some(cards[0]), Some(cards[1]), Some(cards[2])],
-            current_return,
-        )
+        JacksOrBetter::load(s)
     }
+    .map_err(|e| e.to_string())
 }
-pub fn is_sequence((c1, c2, c3): (Card, Card, Card)) -> bool {
-    let mut suites = vec![c1.rank, c2.rank, c3.rank];
-    suites.sort();
-    let c0 = suites[0] as u32;
-    let c1 = suites[1] as u32;
-    let c2 = suites[2] as u32;
-
-    (c0 + 2 == c1 + 1 && c1 + 1 == c2) || suites == [Rank::A, Rank::Queen, Rank::King]
+fn parse_guess(s: &str) -> Result<HandPayGuess, String> {
+    let pays = s
+        .split(',')
+        .map(|pay| match pay.trim() {
+            "_" => Ok(None),
+            pay => pay.parse::<usize>().map(Some).map_err(|e| e.to_string()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let expected = Hand::categories(3).len();
+    if pays.len() != expected {
+        return Err(format!("expected {} pays, found {}", expected, pays.len()));
+    }
+    if pays[0].is_none() {
+        return Err("the best hand's pay must be set".to_string());
+    }
+    Ok(HandPayGuess { pay: pays })
 }
-fn is_same_suit(cards: (Card, Card, Card)) -> bool {
-    cards.0.suit == cards.1.suit && cards.1.suit == cards.2.suit
+fn parse_range(s: &str) -> Result<(Rational, Rational), String> {
+    let (low, high) = s
+        .split_once("..")
+        .ok_or_else(|| format!("expected LOW..HIGH, found {}", s))?;
+    let low = parse_decimal(low).ok_or_else(|| format!("invalid decimal {}", low))?;
+    let high = parse_decimal(high).ok_or_else(|| format!("invalid decimal {}", high))?;
+    if low > high {
+        return Err(format!("empty range {}", s));
+    }
+    Ok((low, high))
 }
-fn is_same_rank(cards: (Card, Card, Card)) -> bool {
-    cards.0.rank == cards.1.rank && cards.1.rank == cards.2.rank
+fn parse_confidence(s: &str) -> Result<f64, String> {
+    let confidence = s.parse::<f64>().map_err(|e| e.to_string())?;
+    if 0.0 < confidence && confidence < 1.0 {
+        Ok(confidence)
+    } else {
+        Err(format!("confidence must be between 0 and 1, found {}", s))
+    }
 }
-fn is_two_same_rank((c1, c2, c3): (Card, Card, Card)) -> bool {
-    let mut suites = vec![c1.rank, c2.rank, c3.rank];
-    suites.sort();
-    suites[0] == suites[1] || suites[1] == suites[2]
+fn card_name(card: &Card, glyphs: bool) -> String {
+    if glyphs {
+        format!("{:#}", card)
+    } else {
+        card.to_string()
+    }
+}
+/// Parses a hand dealt from a standard deck, given either as separate
+/// arguments or as one string
+fn to_hand<const N: usize>(cards: &[String]) -> [Card; N] {
+    Deck::standard()
+        .parse_hand(&cards.join(" "))
+        .unwrap_or_else(|e| {
+            Cli::command()
+                .error(clap::error::ErrorKind::ValueValidation, e)
+                .exit()
+        })
 }
-fn get_hand(cards: &[Card; 3]) -> Hand {
-    if is_sequence((cards[0], cards[1], cards[2])) {
-        if is_same_suit((cards[0], cards[1], cards[2])) {
-            return Hand::StraightFlush;
-        } else {
-            return Hand::Straight;
+/// Parses a hand dealt from the game's deck, given either as separate
+/// arguments or as one string
+fn to_game_hand<const N: usize>(cards: &[String], game: &Game) -> [Card; N] {
+    game.deck.parse_hand(&cards.join(" ")).unwrap_or_else(|e| {
+        Cli::command()
+            .error(clap::error::ErrorKind::ValueValidation, e)
+            .exit()
+    })
+}
+/// Marks the cards of `hand` kept by `hold`, cards written as in the hand,
+/// e.g. `8c 9c`
+fn to_hold<const N: usize>(hand: &[Card; N], hold: &str) -> [bool; N] {
+    let mut kept = [false; N];
+    for name in hold.split(|c: char| c.is_whitespace() || c == ',') {
+        if name.is_empty() {
+            continue;
+        }
+        let card = name.parse::<Card>().map_err(|e| e.to_string());
+        let position = card.and_then(|card| {
+            (0..N)
+                .find(|i| hand[*i] == card && !kept[*i])
+                .ok_or_else(|| format!("{} is not in the dealt hand", card))
+        });
+        match position {
+            Ok(i) => kept[i] = true,
+            Err(e) => Cli::command()
+                .error(clap::error::ErrorKind::ValueValidation, e)
+                .exit(),
         }
     }
-    if is_same_suit((cards[0], cards[1], cards[2])) {
-        return Hand::Flush;
-    }
-    if is_same_rank((cards[0], cards[1], cards[2])) {
-        return Hand::ThreeOfAKind;
+    kept
+}
+/// held cards by name, discarded cards are `None`
+fn hold_names<const N: usize>(hold: &[Option<Card>; N], glyphs: bool) -> Vec<Option<String>> {
+    hold.iter()
+        .map(|card| card.as_ref().map(|card| card_name(card, glyphs)))
+        .collect()
+}
+/// Reads the positions of the cards to keep, e.g. `13` or `1 3`, nothing
+/// draws every card
+fn parse_hold<const N: usize>(s: &str) -> Option<[bool; N]> {
+    let mut hold = [false; N];
+    for c in s.chars().filter(|c| !c.is_whitespace() && *c != ',') {
+        let i = c.to_digit(10)? as usize;
+        if !(1..=N).contains(&i) {
+            return None;
+        }
+        hold[i - 1] = true;
     }
-    if is_two_same_rank((cards[0], cards[1], cards[2])) {
-        return Hand::Pair;
+    Some(hold)
+}
+/// Plays hands from lines of standard input until the player quits or can
+/// not cover the bet
+fn play_terminal(mut session: Session, advisor: bool, glyphs: bool) {
+    let mut lines = std::io::stdin().lock().lines();
+    let mut prompt = |text: &str| {
+        print!("{}", text);
+        std::io::stdout().flush().ok();
+        lines.next()?.ok().map(|line| line.trim().to_string())
+    };
+    loop {
+        println!("credits: {}", session.credits);
+        match prompt("enter deals, q quits > ").as_deref() {
+            None | Some("q") => return,
+            _ => (),
+        }
+        let hand = match session.deal() {
+            Ok(hand) => hand,
+            Err(e) => return println!("{}", e),
+        };
+        let numbered = hand
+            .iter()
+            .enumerate()
+            .map(|(i, card)| format!("{}:{}", i + 1, card_name(card, glyphs)))
+            .collect::<Vec<_>>();
+        println!("dealt: {}", numbered.join(" "));
+        let hold = loop {
+            let Some(line) =
+                prompt("hold which cards, e.g. 13, enter draws all > ").filter(|line| line != "q")
+            else {
+                return;
+            };
+            match parse_hold(&line) {
+                Some(hold) => break hold,
+                None => println!("pick cards by number, 1 to 3"),
+            }
+        };
+        if advisor {
+            let advice = session.advise(hold).expect("a hand is in play");
+            println!(
+                "advisor: best hold {} returns {:.6}, yours returns {:.6}, costing {:.6}",
+                show_hold(&hold_names(&advice.optimal.hold, glyphs)),
+                to_f64(&advice.optimal.expected_return),
+                to_f64(&advice.chosen_return),
+                to_f64(&advice.cost)
+            );
+        }
+        let round = session.draw(hold).expect("a hand is in play");
+        let cards = round
+            .cards
+            .iter()
+            .map(|card| card_name(card, glyphs))
+            .collect::<Vec<_>>();
+        println!(
+            "final: {}, {} pays {}",
+            cards.join(" "),
+            round.category,
+            round.pay
+        );
     }
-    Hand::HighCard
 }
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Card {
-    pub rank: Rank,
-    pub suit: Suit,
-}
-fn generate_deck() -> Vec<Card> {
-    (Suit::Clubs..=Suit::Diamonds)
-        .flat_map(|suit| (Rank::A..=Rank::King).map(move |rank| Card { rank, suit }))
+fn show_hold(hold: &[Option<String>]) -> String {
+    hold.iter()
+        .map(|card| card.as_deref().unwrap_or("--"))
         .collect::<Vec<_>>()
+        .join(" ")
+}
+/// Exact value printed as a decimal, with the fraction kept alongside
+#[derive(Serialize)]
+struct Exact {
+    decimal: f64,
+    exact: String,
+}
+impl From<Rational> for Exact {
+    fn from(value: Rational) -> Self {
+        Self {
+            decimal: to_f64(&value),
+            exact: value.to_string(),
+        }
+    }
 }
-#[derive(Clone, Debug)]
-struct HandPayGuess {
-    pub pay: [Option<usize>; Hand::HighCard as usize + 1],
+impl fmt::Display for Exact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.6} ({})", self.decimal, self.exact)
+    }
+}
+#[derive(Serialize)]
+struct Evaluation {
+    paytable: Paytable,
+    expected_return: Exact,
+    variance: Exact,
+    standard_deviation: f64,
+    hit_frequency: Exact,
+    volatility_index: Vec<Volatility>,
 }
-struct HandTable {
-    hands: [usize; Hand::HighCard as usize + 1],
+#[derive(Serialize)]
+struct Volatility {
+    confidence: f64,
+    index: f64,
 }
-impl std::ops::Add for HandTable {
-    type Output = Self;
-    fn add(mut self, other: Self) -> Self {
-        for i in 0..self.hands.len() {
-            self.hands[i] += other.hands[i]
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Evaluations(Vec<Evaluation>);
+impl fmt::Display for Evaluations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for evaluation in self.0.iter() {
+            let pays = evaluation
+                .paytable
+                .pays()
+                .iter()
+                .map(|pay| pay.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            // paytables given on the command line are named by their pays
+            if evaluation.paytable.name == pays {
+                writeln!(f, "{} return: {}", pays, evaluation.expected_return)?;
+            } else {
+                writeln!(
+                    f,
+                    "{} ({}) return: {}",
+                    evaluation.paytable.name, pays, evaluation.expected_return
+                )?;
+            }
+            writeln!(f, "  variance:           {}", evaluation.variance)?;
+            writeln!(
+                f,
+                "  standard deviation: {:.6}",
+                evaluation.standard_deviation
+            )?;
+            writeln!(f, "  hit frequency:      {}", evaluation.hit_frequency)?;
+            for volatility in evaluation.volatility_index.iter() {
+                writeln!(
+                    f,
+                    "  volatility ({:>4}%): {:.6}",
+                    volatility.confidence * 100.0,
+                    volatility.index
+                )?;
+            }
         }
-        self
+        Ok(())
     }
 }
-impl HandTable {
-    pub fn push_sequence(&mut self, cards: [Card; 3]) {
-        let hand = get_hand(&cards);
-        self.hands[hand as usize] += 1;
+#[derive(Serialize)]
+struct Classification {
+    cards: Vec<String>,
+    hand: String,
+}
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.cards.join(" "), self.hand)
     }
-    pub fn calculate_return(&self, guess: &HandPayGuess) -> f32 {
-        let total: usize = self.hands.iter().sum();
-        let probs = self.hands.iter().map(|n| *n as f32 / (total as f32));
-        probs
-            .zip(guess.pay.iter())
-            .filter(|(_prob, pay)| pay.is_some())
-            .map(|(prob, pay)| (prob, pay.unwrap()))
-            .map(|(prob, pay)| prob * (pay as f32))
-            .fold(0.0, |acc, x| acc + x)
+}
+#[derive(Serialize)]
+struct HoldReturn {
+    hold: Vec<Option<String>>,
+    expected_return: Exact,
+}
+#[derive(Serialize)]
+struct Advice {
+    cards: Vec<String>,
+    hold: Vec<Option<String>>,
+    expected_return: Exact,
+    holds: Vec<HoldReturn>,
+}
+impl fmt::Display for Advice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "dealt: {}", self.cards.join(" "))?;
+        writeln!(
+            f,
+            "hold:  {} (return {})",
+            show_hold(&self.hold),
+            self.expected_return
+        )?;
+        writeln!(f, "{:^16}|{:>11}| exact", "Hold", "return")?;
+        writeln!(f, "------------------------------------------")?;
+        for hold in self.holds.iter() {
+            writeln!(
+                f,
+                "{:^16}|{:>11.6}| {}",
+                show_hold(&hold.hold),
+                hold.expected_return.decimal,
+                hold.expected_return.exact
+            )?;
+        }
+        Ok(())
     }
-    fn calcualte_return_full(&self, guess: &[usize; Hand::HighCard as usize + 1]) -> f32 {
-        let total: usize = self.hands.iter().sum();
-        let probs = self.hands.iter().map(|n| *n as f32 / (total as f32));
-        probs
-            .zip(guess.iter())
-            .map(|(prob, pay)| prob * (*pay as f32))
-            .fold(0.0, |acc, x| acc + x)
+}
+#[derive(Serialize)]
+struct RankedHold {
+    rank: usize,
+    hold: Vec<Option<String>>,
+    expected_return: Exact,
+    cost: Exact,
+}
+#[derive(Serialize)]
+struct HoldCost {
+    cards: Vec<String>,
+    hold: Vec<Option<String>>,
+    expected_return: Exact,
+    rank: usize,
+    optimal_hold: Vec<Option<String>>,
+    optimal_return: Exact,
+    cost: Exact,
+    holds: Vec<RankedHold>,
+}
+impl fmt::Display for HoldCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "dealt:   {}", self.cards.join(" "))?;
+        writeln!(
+            f,
+            "hold:    {} returns {}, rank {} of {}",
+            show_hold(&self.hold),
+            self.expected_return,
+            self.rank,
+            self.holds.len()
+        )?;
+        writeln!(
+            f,
+            "optimal: {} returns {}",
+            show_hold(&self.optimal_hold),
+            self.optimal_return
+        )?;
+        writeln!(f, "cost:    {}", self.cost)?;
+        writeln!(
+            f,
+            "{:>4} |{:^16}|{:>11}|{:>11}",
+            "rank", "Hold", "return", "cost"
+        )?;
+        writeln!(f, "---------------------------------------------")?;
+        for hold in self.holds.iter() {
+            writeln!(
+                f,
+                "{:>4} |{:^16}|{:>11.6}|{:>11.6}",
+                hold.rank,
+                show_hold(&hold.hold),
+                hold.expected_return.decimal,
+                hold.cost.decimal
+            )?;
+        }
+        Ok(())
+    }
+}
+#[derive(Serialize)]
+struct ChartLine {
+    rule: String,
+    frequency: Exact,
+}
+#[derive(Serialize)]
+struct ChartReport {
+    rules: Vec<ChartLine>,
+    optimal_return: Exact,
+    chart_return: Exact,
+    cost: Exact,
+}
+impl fmt::Display for ChartReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.rules.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3}. {:<40} {:>8.4}%",
+                i + 1,
+                line.rule,
+                line.frequency.decimal * 100.0
+            )?;
+        }
+        writeln!(f, "perfect play returns {}", self.optimal_return)?;
+        writeln!(f, "the chart returns    {}", self.chart_return)?;
+        writeln!(f, "giving up            {}", self.cost)
     }
-    /// Uses brute force to find the pay table that fits inside
-    /// of the given range with the pays in decending order,
-    /// assumes first pay is filled out and lays out unfilled pays in decending order
-    pub fn build_paytable(
-        &self,
-        guess: HandPayGuess,
-        low_return: f32,
-        high_return: f32,
-    ) -> Option<HandPayGuess> {
-        let mut check_idx = guess
-            .pay
+}
+#[derive(Serialize)]
+struct BankrollBin {
+    low: usize,
+    high: usize,
+    sessions: usize,
+}
+#[derive(Serialize)]
+struct SimulationSummary {
+    sessions: usize,
+    bankroll: usize,
+    risk_of_ruin: f64,
+    double_rate: f64,
+    mean_hands_to_bust: Option<f64>,
+    mean_hands_to_double: Option<f64>,
+    mean_final_bankroll: Option<f64>,
+    /// final bankroll at the 5th, 25th, 50th, 75th and 95th percentiles
+    percentiles: Vec<(usize, Option<usize>)>,
+    histogram: Vec<BankrollBin>,
+}
+impl fmt::Display for SimulationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mean = |mean: Option<f64>| mean.map_or("-".to_string(), |mean| format!("{:.1}", mean));
+        writeln!(
+            f,
+            "{} sessions from {} credits",
+            self.sessions, self.bankroll
+        )?;
+        writeln!(f, "risk of ruin:      {:.4}", self.risk_of_ruin)?;
+        writeln!(f, "doubled:           {:.4}", self.double_rate)?;
+        writeln!(f, "hands to bust:     {}", mean(self.mean_hands_to_bust))?;
+        writeln!(f, "hands to double:   {}", mean(self.mean_hands_to_double))?;
+        writeln!(f, "final bankroll:    {}", mean(self.mean_final_bankroll))?;
+        for (percent, bankroll) in self.percentiles.iter() {
+            if let Some(bankroll) = bankroll {
+                writeln!(f, "  {:>2}th percentile: {}", percent, bankroll)?;
+            }
+        }
+        let most = self
+            .histogram
             .iter()
-            .enumerate()
-            .filter(|(_i, pay)| pay.is_none())
-            .map(|(i, _pay)| i);
-        let expected_pay = self.calculate_return(&guess);
-        if expected_pay > high_return {
-            None
-        } else if expected_pay > low_return {
-            Some(guess)
-        } else if let Some(idx) = check_idx.next() {
-            let mut guesses = (0..guess.pay[idx - 1].unwrap())
-                .rev()
-                .map(|guess_pay| {
-                    let mut guess = guess.clone();
-                    guess.pay[idx] = Some(guess_pay);
-                    self.build_paytable(guess, low_return, high_return)
-                })
-                .flatten();
-            guesses.next()
-        } else {
-            None
+            .map(|bin| bin.sessions)
+            .max()
+            .unwrap_or(0);
+        for bin in self.histogram.iter() {
+            writeln!(
+                f,
+                "{:>8}..{:<8} {:>6} {}",
+                bin.low,
+                bin.high,
+                bin.sessions,
+                "#".repeat(bin.sessions * 40 / most.max(1))
+            )?;
         }
+        Ok(())
     }
 }
-pub struct PrintTable {}
-impl std::fmt::Display for HandTable {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{:^20}|{:>6}", "Hand", "n")?;
-        writeln!(f, "----------------------------")?;
-        for (i, num) in self.hands.iter().enumerate() {
-            let hand: Hand = i.into();
-            writeln!(f, "{:^20}|{:>6}", format!("{:?}", hand), num)?;
+#[derive(Serialize)]
+struct Frequencies {
+    hands: Vec<(String, usize)>,
+}
+impl fmt::Display for Frequencies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:^20}|{:>8}", "Hand", "n")?;
+        writeln!(f, "------------------------------")?;
+        for (hand, n) in self.hands.iter() {
+            writeln!(f, "{:^20}|{:>8}", hand, n)?;
         }
         Ok(())
     }
 }
-impl Default for HandTable {
-    fn default() -> Self {
-        Self {
-            hands: [0; Hand::HighCard as usize + 1],
+#[derive(Serialize)]
+struct Candidate {
+    paytable: Vec<usize>,
+    expected_return: Exact,
+}
+#[derive(Serialize)]
+struct PaytableSearch {
+    candidates: Vec<Candidate>,
+}
+impl fmt::Display for PaytableSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.candidates.is_empty() {
+            writeln!(f, "no paytable found")?;
+        }
+        for candidate in self.candidates.iter() {
+            let pays = candidate
+                .paytable
+                .iter()
+                .map(|pay| pay.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(f, "{} return: {}", pays, candidate.expected_return)?;
         }
+        Ok(())
     }
 }
-fn calculate_expected_return(table: [usize; Hand::HighCard as usize + 1]) -> f32 {
-    let mut total = 0.0f32;
-    let deck = generate_deck();
-
-    for i in 0..deck.len() {
-        for j in 0..deck.len() {
-            for k in 0..deck.len() {
-                if i != j && j != k && i != k {
-                    let cards = [deck[i], deck[j], deck[k]];
-                    let (_play, play_return) = optimal_play(cards, &table);
-
-                    total += play_return / (52.0 * 51.0 * 50.0);
-                }
-            }
+#[derive(Serialize)]
+struct AntePlayReport {
+    ante_bonus: Vec<usize>,
+    play_win: usize,
+    house_edge: Exact,
+    play_frequency: Exact,
+    threshold: Vec<String>,
+}
+impl fmt::Display for AntePlayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "ante bonus {:?}, play pays {} to 1",
+            self.ante_bonus, self.play_win
+        )?;
+        writeln!(f, "house edge:     {}", self.house_edge)?;
+        writeln!(f, "play frequency: {}", self.play_frequency)?;
+        writeln!(f, "play with {} or better", self.threshold.join(" "))
+    }
+}
+#[derive(Serialize)]
+struct PairPlusReport {
+    name: String,
+    pays: Vec<usize>,
+    return_to_player: Exact,
+    house_edge: Exact,
+    variance: Exact,
+    hit_frequency: Exact,
+}
+#[derive(Serialize)]
+#[serde(transparent)]
+struct PairPlusReports(Vec<PairPlusReport>);
+impl fmt::Display for PairPlusReports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for report in self.0.iter() {
+            writeln!(f, "{} {:?}", report.name, report.pays)?;
+            writeln!(f, "  return:        {}", report.return_to_player)?;
+            writeln!(f, "  house edge:    {}", report.house_edge)?;
+            writeln!(f, "  variance:      {}", report.variance)?;
+            writeln!(f, "  hit frequency: {}", report.hit_frequency)?;
         }
+        Ok(())
     }
-    total
-}
-fn generate_all_games() {
-    let deck = generate_deck();
-    let mut table = HandTable::default();
-    for i in 0..deck.len() {
-        for j in 0..deck.len() {
-            for k in 0..deck.len() {
-                if i != j && j != k && i != k {
-                    let c1 = deck[i];
-                    let c2 = deck[j];
-                    let c3 = deck[k];
-                    table.push_sequence([c1, c2, c3]);
-                }
-            }
+}
+#[derive(Serialize)]
+struct VideoPokerReport {
+    paytable: Paytable,
+    expected_return: Exact,
+}
+impl fmt::Display for VideoPokerReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paytable = &self.paytable;
+        for (hand, pay) in paytable.categories().iter().zip(paytable.pays()) {
+            writeln!(f, "{:>16}: {}", hand, pay)?;
         }
+        writeln!(f, "return: {}", self.expected_return)
     }
-    println!("{}", table);
-    let pay_table = table
-        .build_paytable(
-            HandPayGuess {
-                pay: [Some(250), None, None, None, None, Some(0)],
-            },
-            0.99,
-            1.00,
-        )
-        .unwrap();
-    println!("{:?}", pay_table);
-    println!("return: {}", table.calculate_return(&pay_table));
-}
-fn run_experiment(name: &str, table: [usize; 6]) {
-    let game_return = calculate_expected_return(table);
-    println!("{} return: {}", name, game_return);
-}
-fn main() -> Result<(), ()> {
-    run_experiment("assignment 3", [100, 99, 9, 5, 0, 0]);
-    run_experiment("1", [100, 50, 9, 5, 0, 0]);
-    run_experiment("2", [50, 25, 5, 2, 0, 0]);
-
-    let game_return = calculate_expected_return([50, 10, 5, 2, 0, 0]);
-    println!("return: {}", game_return);
-    let game_return = calculate_expected_return([30, 10, 3, 2, 0, 0]);
-    println!("return: {}", game_return);
-    let game_return = calculate_expected_return([40, 10, 3, 2, 0, 0]);
-    println!("return: {}", game_return);
-    let game_return = calculate_expected_return([45, 10, 3, 2, 0, 0]);
-    println!("return: {}", game_return);
-    let game_return = calculate_expected_return([48, 10, 3, 2, 0, 0]);
-    println!("return: {}", game_return);
-    let game_return = calculate_expected_return([49, 10, 3, 2, 0, 0]);
-    println!("return: {}", game_return);
-    let game_return = calculate_expected_return([49, 11, 3, 2, 0, 0]);
-    println!("return: {}", game_return);
-    run_experiment("10", [49, 12, 3, 2, 0, 0]);
-    run_experiment("11", [49, 15, 3, 2, 0, 0]);
-    run_experiment("12", [49, 17, 3, 2, 0, 0]);
-    run_experiment("12", [49, 16, 3, 2, 0, 0]);
-    Ok(())
-}
-#[cfg(test)]
-mod test {
-    use super::*;
-    #[test]
-    fn t_optimal_play() {
-        let hand = [
-            Card {
-                rank: Rank::Eight,
-                suit: Suit::Clubs,
-            },
-            Card {
-                rank: Rank::Nine,
-                suit: Suit::Clubs,
-            },
-            Card {
-                rank: Rank::Ten,
-                suit: Suit::Clubs,
-            },
-        ];
-        let best_hand = optimal_play(hand, [1, 0, 0, 0, 0, 0]);
-        assert_eq!(
-            best_hand.0,
-            [
-                Some(Card {
-                    rank: Rank::Eight,
-                    suit: Suit::Clubs,
-                }),
-                Some(Card {
-                    rank: Rank::Nine,
-                    suit: Suit::Clubs,
-                }),
-                Some(Card {
-                    rank: Rank::Ten,
-                    suit: Suit::Clubs,
-                }),
-            ]
-        );
+}
+fn emit<T: Serialize + fmt::Display>(format: Format, report: &T) {
+    match format {
+        Format::Human => print!("{}", report),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(report).expect("reports always serialize")
+        ),
     }
-    #[test]
-    fn sequence() {
-        let hand = (
-            Card {
-                rank: Rank::A,
-                suit: Suit::Clubs,
-            },
-            Card {
-                rank: Rank::Two,
-                suit: Suit::Clubs,
-            },
-            Card {
-                rank: Rank::Three,
-                suit: Suit::Clubs,
-            },
-        );
-        assert_eq!(is_sequence(hand), true);
-        let hand = (
-            Card {
-                rank: Rank::A,
-                suit: Suit::Clubs,
-            },
-            Card {
-                rank: Rank::Two,
-                suit: Suit::Clubs,
-            },
-            Card {
-                rank: Rank::Four,
-                suit: Suit::Clubs,
-            },
-        );
-        assert_eq!(is_sequence(hand), false);
+}
+fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Evaluate {
+            paytable,
+            confidence,
+            game,
+        } => {
+            let outcomes = GameOutcomes::<3>::for_game(&game.game());
+            let evaluations = paytable
+                .into_iter()
+                .map(|paytable| {
+                    let stats = outcomes.statistics(&paytable);
+                    Evaluation {
+                        expected_return: stats.expected_return.into(),
+                        variance: stats.variance.into(),
+                        standard_deviation: stats.standard_deviation(),
+                        hit_frequency: stats.hit_frequency.into(),
+                        volatility_index: confidence
+                            .iter()
+                            .map(|confidence| Volatility {
+                                confidence: *confidence,
+                                index: stats.volatility_index(*confidence),
+                            })
+                            .collect(),
+                        paytable,
+                    }
+                })
+                .collect();
+            emit(cli.format, &Evaluations(evaluations))
+        }
+        Command::Classify { cards, game } => {
+            let game = game.game();
+            let cards = to_game_hand::<3>(&cards, &game);
+            emit(
+                cli.format,
+                &Classification {
+                    cards: cards
+                        .iter()
+                        .map(|card| card_name(card, cli.glyphs))
+                        .collect(),
+                    hand: {
+                        let categories = game.hand_categories(3);
+                        categories.names()[categories.classify(&game.evaluator, &cards)].to_string()
+                    },
+                },
+            )
+        }
+        Command::Advise {
+            cards,
+            paytable,
+            game,
+        } => {
+            let game = game.game();
+            let cards = to_game_hand::<3>(&cards, &game);
+            let play = game.optimal_play(cards, &paytable);
+            emit(
+                cli.format,
+                &Advice {
+                    cards: cards
+                        .iter()
+                        .map(|card| card_name(card, cli.glyphs))
+                        .collect(),
+                    hold: hold_names(&play.hold, cli.glyphs),
+                    expected_return: play.expected_return.into(),
+                    holds: play
+                        .holds
+                        .iter()
+                        .map(|(hold, expected_return)| HoldReturn {
+                            hold: hold_names(hold, cli.glyphs),
+                            expected_return: (*expected_return).into(),
+                        })
+                        .collect(),
+                },
+            )
+        }
+        Command::Cost {
+            cards,
+            hold,
+            paytable,
+            game,
+        } => {
+            let game = game.game();
+            let cards = to_game_hand::<3>(&cards, &game);
+            let advice = game.hold_cost(cards, to_hold(&cards, &hold), &paytable);
+            let best = advice.optimal.expected_return;
+            let ranked = advice.optimal.ranked_holds();
+            let holds = ranked
+                .iter()
+                .map(|(hold, expected_return)| RankedHold {
+                    rank: 1 + ranked.iter().filter(|(_, r)| r > expected_return).count(),
+                    hold: hold_names(hold, cli.glyphs),
+                    expected_return: (*expected_return).into(),
+                    cost: (best - expected_return).into(),
+                })
+                .collect();
+            emit(
+                cli.format,
+                &HoldCost {
+                    cards: cards
+                        .iter()
+                        .map(|card| card_name(card, cli.glyphs))
+                        .collect(),
+                    hold: hold_names(&advice.hold, cli.glyphs),
+                    expected_return: advice.chosen_return.into(),
+                    rank: advice.rank,
+                    optimal_hold: hold_names(&advice.optimal.hold, cli.glyphs),
+                    optimal_return: best.into(),
+                    cost: advice.cost.into(),
+                    holds,
+                },
+            )
+        }
+        Command::Chart { paytable, game } => {
+            let outcomes = GameOutcomes::<3>::for_game(&game.game());
+            let chart = StrategyChart::new(&outcomes, &paytable);
+            emit(
+                cli.format,
+                &ChartReport {
+                    optimal_return: chart.optimal_return.into(),
+                    chart_return: chart.chart_return.into(),
+                    cost: chart.cost().into(),
+                    rules: chart
+                        .rules
+                        .into_iter()
+                        .map(|rule| ChartLine {
+                            rule: rule.description,
+                            frequency: rule.frequency.into(),
+                        })
+                        .collect(),
+                },
+            )
+        }
+        Command::Play {
+            paytable,
+            credits,
+            seed,
+            advisor,
+            game,
+        } => {
+            let game = game.game();
+            let seed = seed.unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |time| time.as_nanos() as u64)
+            });
+            println!("seed: {}", seed);
+            let session = Session::new(game, paytable, credits, seed);
+            play_terminal(session, advisor, cli.glyphs)
+        }
+        Command::Simulate {
+            paytable,
+            bankroll,
+            coins,
+            hands,
+            sessions,
+            seed,
+            strategy,
+            bins,
+            game,
+        } => {
+            let simulation = Simulation::<3> {
+                game: game.game(),
+                coins,
+                ..Simulation::new(paytable, bankroll, hands, sessions, seed)
+            };
+            let report = simulation
+                .with_strategy(match strategy {
+                    Strategy::Optimal => HoldStrategy::Optimal,
+                    Strategy::HoldEverything => HoldStrategy::HoldEverything,
+                })
+                .run();
+            emit(
+                cli.format,
+                &SimulationSummary {
+                    sessions: report.sessions.len(),
+                    bankroll: report.bankroll,
+                    risk_of_ruin: report.risk_of_ruin(),
+                    double_rate: report.double_rate(),
+                    mean_hands_to_bust: report.mean_hands_to_bust(),
+                    mean_hands_to_double: report.mean_hands_to_double(),
+                    mean_final_bankroll: report.mean_final_bankroll(),
+                    percentiles: [5, 25, 50, 75, 95]
+                        .into_iter()
+                        .map(|percent| (percent, report.percentile(percent as f64 / 100.0)))
+                        .collect(),
+                    histogram: report
+                        .histogram(bins as usize)
+                        .into_iter()
+                        .map(|bin| BankrollBin {
+                            low: bin.low,
+                            high: bin.high,
+                            sessions: bin.sessions,
+                        })
+                        .collect(),
+                },
+            )
+        }
+        Command::Table { game } => {
+            let table = game.game().all_deals::<3>();
+            emit(
+                cli.format,
+                &Frequencies {
+                    hands: table
+                        .names()
+                        .iter()
+                        .cloned()
+                        .zip(table.counts().iter().copied())
+                        .collect(),
+                },
+            )
+        }
+        Command::AntePlay {
+            ante_bonus,
+            play_win,
+        } => {
+            let rules = match AntePlayRules::new(ante_bonus.clone(), play_win) {
+                Ok(rules) => rules,
+                Err(e) => Cli::command()
+                    .error(clap::error::ErrorKind::ValueValidation, e)
+                    .exit(),
+            };
+            let analysis = analyze_ante_play(&rules);
+            let mut threshold = analysis.threshold;
+            threshold.sort_by(|a, b| b.cmp(a));
+            emit(
+                cli.format,
+                &AntePlayReport {
+                    ante_bonus,
+                    play_win,
+                    house_edge: analysis.house_edge().into(),
+                    play_frequency: analysis.play_frequency.into(),
+                    threshold: threshold
+                        .iter()
+                        .map(|card| card_name(card, cli.glyphs))
+                        .collect(),
+                },
+            )
+        }
+        Command::PairPlus { pays } => {
+            let bets = match pays {
+                Some(pays) => {
+                    let name = pays
+                        .iter()
+                        .map(|pay| pay.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    match PairPlus::new(name, pays) {
+                        Ok(bet) => vec![bet],
+                        Err(e) => Cli::command()
+                            .error(clap::error::ErrorKind::ValueValidation, e)
+                            .exit(),
+                    }
+                }
+                None => PairPlus::presets(),
+            };
+            let reports = bets
+                .into_iter()
+                .map(|bet| {
+                    let stats = bet.statistics();
+                    PairPlusReport {
+                        return_to_player: stats.expected_return.into(),
+                        house_edge: bet.house_edge().into(),
+                        variance: stats.variance.into(),
+                        hit_frequency: stats.hit_frequency.into(),
+                        name: bet.name,
+                        pays: bet.pays,
+                    }
+                })
+                .collect();
+            emit(cli.format, &PairPlusReports(reports))
+        }
+        Command::JacksOrBetter {
+            cards,
+            pays: paytable,
+        } => {
+            let engine = VideoPoker::new();
+            if cards.is_empty() {
+                return emit(
+                    cli.format,
+                    &VideoPokerReport {
+                        expected_return: engine.expected_return(&paytable).into(),
+                        paytable: paytable.paytable().clone(),
+                    },
+                );
+            }
+            let cards = to_hand::<5>(&cards);
+            let play = engine.optimal_play(cards, &paytable);
+            emit(
+                cli.format,
+                &Advice {
+                    cards: cards
+                        .iter()
+                        .map(|card| card_name(card, cli.glyphs))
+                        .collect(),
+                    hold: hold_names(&play.hold, cli.glyphs),
+                    expected_return: play.expected_return.into(),
+                    holds: play
+                        .holds
+                        .iter()
+                        .map(|(hold, expected_return)| HoldReturn {
+                            hold: hold_names(hold, cli.glyphs),
+                            expected_return: (*expected_return).into(),
+                        })
+                        .collect(),
+                },
+            )
+        }
+        Command::SearchPaytable {
+            target_rtp: (low, high),
+            mode,
+            pays,
+            game,
+        } => {
+            let game = game.game();
+            let candidates = match mode {
+                SearchMode::Optimal => {
+                    let outcomes = GameOutcomes::<3>::for_game(&game);
+                    outcomes
+                        .build_paytables(&pays, low, high)
+                        .iter()
+                        .map(|paytable| Candidate {
+                            paytable: paytable.pays().to_vec(),
+                            expected_return: outcomes.expected_return(paytable).into(),
+                        })
+                        .collect()
+                }
+                SearchMode::Dealt => {
+                    let table = game.all_deals::<3>();
+                    table
+                        .build_paytable(pays, low, high)
+                        .map(|pays| Candidate {
+                            expected_return: table.calculate_return(&pays).into(),
+                            // pays left unset once the return is in range pay nothing
+                            paytable: pays.pay.iter().map(|pay| pay.unwrap_or(0)).collect(),
+                        })
+                        .into_iter()
+                        .collect()
+                }
+            };
+            emit(cli.format, &PaytableSearch { candidates })
+        }
     }
 }
@@ -0,0 +1,313 @@
+use super::{
+    strategy::{best_play, hold_mask, hold_tables, Supersets},
+    Categories, DealClass, Evaluator, Game, GameStatistics, HandPayGuess, HandTable, Paytable,
+    Play, Rational,
+};
+
+/// Final hand counts for every hold of one deal class
+#[derive(Clone, Debug)]
+pub struct ClassOutcomes<const N: usize = 3> {
+    pub class: DealClass<N>,
+    /// final hands reachable from each hold, indexed by hold mask where bit
+    /// `i` set keeps `class.cards[i]`
+    pub holds: Vec<HandTable<N>>,
+}
+impl<const N: usize> ClassOutcomes<N> {
+    /// Best hold for the class under `paytable`
+    pub fn play(&self, paytable: &Paytable) -> Play<N> {
+        best_play(self.class.cards, &self.holds, paytable)
+    }
+}
+/// Draw outcomes of every deal class and hold. The counts do not depend on
+/// the paytable, so once built any number of paytables can be evaluated, and
+/// their optimal holds found, without dealing another card.
+#[derive(Clone, Debug)]
+pub struct GameOutcomes<const N: usize = 3> {
+    classes: Vec<ClassOutcomes<N>>,
+    /// categories the final hands are counted in
+    categories: Categories,
+    evaluator: Evaluator,
+    deals: usize,
+    /// common multiple of the number of draws of every hold, so the expected
+    /// pay of any hold is a whole number of `1 / denominator` credits
+    denominator: usize,
+}
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+impl<const N: usize> GameOutcomes<N> {
+    /// Outcomes of the standard game
+    pub fn new() -> Self {
+        Self::for_game(&Game::standard())
+    }
+    pub fn for_game(game: &Game) -> Self {
+        let mut supersets = Supersets::new(game);
+        let classes = game
+            .deal_classes()
+            .into_iter()
+            .map(|class| ClassOutcomes {
+                holds: hold_tables(&class.cards, &mut supersets),
+                class,
+            })
+            .collect::<Vec<_>>();
+        let deals = classes.iter().map(|outcome| outcome.class.weight).sum();
+        let denominator = classes
+            .iter()
+            .flat_map(|outcome| outcome.holds.iter())
+            .map(|table| table.total())
+            .filter(|total| *total > 0)
+            .fold(1, |acc, total| acc / gcd(acc, total) * total);
+        Self {
+            classes,
+            categories: game.hand_categories(N),
+            evaluator: game.evaluator,
+            deals,
+            denominator,
+        }
+    }
+    pub fn classes(&self) -> &[ClassOutcomes<N>] {
+        &self.classes
+    }
+    /// Categories the hold tables count, which a paytable must pay
+    pub fn categories(&self) -> &Categories {
+        &self.categories
+    }
+    /// Evaluator of the game the outcomes were counted for
+    pub fn evaluator(&self) -> &Evaluator {
+        &self.evaluator
+    }
+    /// Number of unordered deals covered by the classes
+    pub fn deals(&self) -> usize {
+        self.deals
+    }
+    /// Optimal play for every class under `paytable`
+    pub fn plays<'a>(
+        &'a self,
+        paytable: &'a Paytable,
+    ) -> impl Iterator<Item = (&'a ClassOutcomes<N>, Play<N>)> + 'a {
+        self.classes
+            .iter()
+            .map(move |outcome| (outcome, outcome.play(paytable)))
+    }
+    /// Expected return per credit bet when every deal is played optimally.
+    ///
+    /// Panics if the deck holds fewer than `N` cards.
+    pub fn expected_return(&self, paytable: &Paytable) -> Rational {
+        self.check(paytable);
+        self.pays_return(paytable.pays(), paytable.bet)
+    }
+    /// Exact distribution of a single game's return under optimal play
+    pub fn statistics(&self, paytable: &Paytable) -> GameStatistics {
+        self.check(paytable);
+        let mut counts = vec![0u128; self.categories.len()];
+        for (outcome, play) in self.plays(paytable) {
+            let table = &outcome.holds[hold_mask(&play.hold)];
+            let scale = (self.denominator / table.total() * outcome.class.weight) as u128;
+            for (count, n) in counts.iter_mut().zip(table.counts()) {
+                *count += *n as u128 * scale;
+            }
+        }
+        let total = (self.denominator * self.deals) as i128;
+        let pays = paytable
+            .pays()
+            .iter()
+            .map(|pay| Rational::new(*pay as i128, paytable.bet as i128))
+            .collect::<Vec<_>>();
+        GameStatistics::new(
+            counts
+                .into_iter()
+                .map(|n| Rational::new(n as i128, total))
+                .collect(),
+            &pays,
+        )
+    }
+    /// Panics unless `paytable` pays this game's hand size and categories
+    fn check(&self, paytable: &Paytable) {
+        assert_eq!(paytable.cards, N, "paytable is for a different hand size");
+        assert_eq!(
+            paytable.categories(),
+            self.categories.names(),
+            "paytable is for different categories"
+        );
+        assert!(self.deals > 0, "the deck deals no hands of {} cards", N);
+    }
+    /// Expected return of pays indexed by category. Each hold's
+    /// expected pay is kept as a whole number of `1 / denominator` credits so
+    /// holds compare and sum without building a fraction per hold.
+    fn pays_return(&self, pays: &[usize], bet: usize) -> Rational {
+        let total: u128 = self
+            .classes
+            .iter()
+            .map(|outcome| {
+                let best = outcome
+                    .holds
+                    .iter()
+                    .filter(|table| table.total() > 0)
+                    .map(|table| {
+                        table.total_pay(pays) as u128 * (self.denominator / table.total()) as u128
+                    })
+                    .max()
+                    .expect("every class has holds");
+                best * outcome.class.weight as u128
+            })
+            .sum();
+        Rational::new(total as i128, (self.denominator * self.deals * bet) as i128)
+    }
+    /// Finds every descending paytable that completes `guess` and whose
+    /// return under optimal play lands in `low_return..=high_return`.
+    ///
+    /// Unset pays are searched from the pay above them down to the next set
+    /// pay below them (or zero). Raising any pay never lowers the optimal
+    /// return, so a branch is dropped once even its lowest completion pays
+    /// too much or its highest completion pays too little.
+    ///
+    /// Panics if the best hand's pay is not set.
+    pub fn build_paytables(
+        &self,
+        guess: &HandPayGuess,
+        low_return: Rational,
+        high_return: Rational,
+    ) -> Vec<Paytable> {
+        assert!(guess.pay[0].is_some(), "the best hand's pay must be set");
+        let mut found = vec![];
+        self.search(guess.clone(), low_return, high_return, &mut found);
+        found
+    }
+    fn search(
+        &self,
+        guess: HandPayGuess,
+        low_return: Rational,
+        high_return: Rational,
+        found: &mut Vec<Paytable>,
+    ) {
+        // every unset pay at its smallest and largest allowed value
+        let mut lowest = vec![0; guess.pay.len()];
+        let mut highest = vec![0; guess.pay.len()];
+        for i in 0..guess.pay.len() {
+            highest[i] = guess.pay[i].unwrap_or_else(|| highest[i - 1]);
+        }
+        for i in (0..guess.pay.len()).rev() {
+            lowest[i] = guess.pay[i].unwrap_or(lowest.get(i + 1).copied().unwrap_or(0));
+        }
+        if self.pays_return(&lowest, 1) > high_return || self.pays_return(&highest, 1) < low_return
+        {
+            return;
+        }
+        match guess.pay.iter().position(|pay| pay.is_none()) {
+            None => {
+                let name = lowest
+                    .iter()
+                    .map(|pay| pay.to_string())
+                    .collect::<Vec<_>>()
+                    .join("-");
+                if let Ok(paytable) = Paytable::for_categories(name, N, &self.categories, lowest) {
+                    found.push(paytable);
+                }
+            }
+            Some(idx) => {
+                for pay in (lowest[idx]..=highest[idx]).rev() {
+                    let mut guess = guess.clone();
+                    guess.pay[idx] = Some(pay);
+                    self.search(guess, low_return, high_return, found);
+                }
+            }
+        }
+    }
+}
+impl<const N: usize> Default for GameOutcomes<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Hand;
+    #[test]
+    fn reuse_for_paytables() {
+        let outcomes = GameOutcomes::<3>::new();
+        assert_eq!(outcomes.deals(), 22100);
+        let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+        assert_eq!(
+            outcomes.expected_return(&paytable),
+            Rational::new(9219467, 2993900)
+        );
+        // pays everything the same, so every hold returns exactly the pay
+        let flat = Paytable::new("flat", [2, 2, 2, 2, 2, 2]).unwrap();
+        assert_eq!(outcomes.expected_return(&flat), Rational::from_integer(2));
+        for (outcome, play) in outcomes.plays(&flat) {
+            assert_eq!(play.hold, outcome.class.cards.map(Some));
+        }
+    }
+    #[test]
+    fn paytables_in_band() {
+        let outcomes = GameOutcomes::<3>::new();
+        let guess = HandPayGuess {
+            pay: vec![Some(30), None, None, None, Some(0), Some(0)],
+        };
+        let low = Rational::new(99, 100);
+        let high = Rational::from_integer(1);
+        let found = outcomes.build_paytables(&guess, low, high);
+        assert!(!found.is_empty());
+        for paytable in found.iter() {
+            let expected_return = outcomes.expected_return(paytable);
+            assert!(low <= expected_return && expected_return <= high);
+            assert_eq!(paytable.pay(Hand::StraightFlush), 30);
+        }
+        // the dealt hand fit ignores the draw and overshoots
+        let dealt = HandTable::<3>::all_deals()
+            .build_paytable(guess, low, high)
+            .unwrap();
+        let pays = dealt
+            .pay
+            .iter()
+            .map(|pay| pay.unwrap_or(0))
+            .collect::<Vec<_>>();
+        let paytable = Paytable::new("dealt", pays).unwrap();
+        assert!(outcomes.expected_return(&paytable) > high);
+    }
+    #[test]
+    fn statistics() {
+        let outcomes = GameOutcomes::<3>::new();
+        let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+        let stats = outcomes.statistics(&paytable);
+        assert_eq!(stats.expected_return, outcomes.expected_return(&paytable));
+        assert_eq!(
+            stats.hand_probabilities.iter().sum::<Rational>(),
+            Rational::from_integer(1)
+        );
+        assert_eq!(
+            stats.hit_frequency,
+            stats.hand_probabilities[..4].iter().sum::<Rational>()
+        );
+        assert!(stats.variance > Rational::from_integer(0));
+        let flat = Paytable::new("flat", [2, 2, 2, 2, 2, 2]).unwrap();
+        let stats = outcomes.statistics(&flat);
+        assert_eq!(stats.variance, Rational::from_integer(0));
+        assert_eq!(stats.hit_frequency, Rational::from_integer(1));
+    }
+    #[test]
+    fn tiny_deck() {
+        use crate::{Deck, Rank, Suit};
+        // two aces and two kings: one card is left after the deal, so only
+        // holds discarding at most one card can draw
+        let game = Game::from_deck(Deck::new(
+            [Rank::A, Rank::King],
+            [Suit::Clubs, Suit::Hearts],
+        ));
+        let outcomes = GameOutcomes::<3>::for_game(&game);
+        assert_eq!(outcomes.deals(), 4);
+        let paytable = Paytable::new("pairs", [5, 4, 3, 2, 1, 0]).unwrap();
+        // every hand of three is a pair, whatever is drawn
+        assert_eq!(
+            outcomes.expected_return(&paytable),
+            Rational::from_integer(1)
+        );
+        let (_, play) = outcomes.plays(&paytable).next().unwrap();
+        assert_eq!(play.holds.len(), 4);
+    }
+}
@@ -0,0 +1,97 @@
+use super::{GameStatistics, HandTable, Paytable, PaytableError, Rational};
+
+/// Pair Plus side bet, paid on the player's three cards alone without regard
+/// to the dealer's hand
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PairPlus {
+    pub name: String,
+    /// pays to one in the order of the three card
+    /// [`Hand::categories`](crate::Hand::categories), a zero pay loses the bet
+    pub pays: Vec<usize>,
+}
+impl PairPlus {
+    /// Fails unless there is a pay for every three card category and no
+    /// hand pays more than a better one, as a [`Paytable`] must
+    pub fn new(
+        name: impl Into<String>,
+        pays: impl Into<Vec<usize>>,
+    ) -> Result<Self, PaytableError> {
+        let paytable = Paytable::new(name, pays)?;
+        Ok(Self {
+            pays: paytable.pays().to_vec(),
+            name: paytable.name,
+        })
+    }
+    /// Paytables commonly found in casinos, the most widespread first
+    pub fn presets() -> Vec<Self> {
+        [
+            [40, 30, 6, 4, 1, 0],
+            [40, 30, 6, 3, 1, 0],
+            [40, 30, 5, 4, 1, 0],
+            [35, 25, 6, 4, 1, 0],
+            [50, 40, 6, 3, 1, 0],
+        ]
+        .into_iter()
+        .map(|pays| {
+            let name = pays[..pays.len() - 1]
+                .iter()
+                .map(|pay| pay.to_string())
+                .collect::<Vec<_>>()
+                .join("-");
+            Self::new(name, pays).expect("the presets pay every category")
+        })
+        .collect()
+    }
+    /// Exact distribution of the amount returned per unit bet, including the
+    /// bet itself, over every possible deal
+    pub fn statistics(&self) -> GameStatistics {
+        let table = HandTable::<3>::all_deals();
+        let total = table.total() as i128;
+        let probabilities = table
+            .counts()
+            .iter()
+            .map(|n| Rational::new(*n as i128, total))
+            .collect();
+        let returns = self
+            .pays
+            .iter()
+            .map(|pay| match *pay {
+                0 => Rational::from_integer(0),
+                pay => Rational::from_integer(pay as i128 + 1),
+            })
+            .collect::<Vec<_>>();
+        GameStatistics::new(probabilities, &returns)
+    }
+    /// Return to player per unit bet
+    pub fn return_to_player(&self) -> Rational {
+        self.statistics().expected_return
+    }
+    pub fn house_edge(&self) -> Rational {
+        Rational::from_integer(1) - self.return_to_player()
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn common_paytables() {
+        let presets = PairPlus::presets();
+        assert_eq!(presets[0].name, "40-30-6-4-1");
+        // the familiar 2.32% and 7.28% house edges
+        assert_eq!(presets[0].house_edge(), Rational::new(512, 22100));
+        assert_eq!(presets[1].house_edge(), Rational::new(1608, 22100));
+        let stats = presets[0].statistics();
+        assert_eq!(stats.hit_frequency, Rational::new(22100 - 16440, 22100));
+        assert!(matches!(
+            PairPlus::new("short", [40, 30, 6]),
+            Err(PaytableError::WrongCount {
+                expected: 6,
+                found: 3
+            })
+        ));
+        assert!(matches!(
+            PairPlus::new("backwards", [1, 4, 6, 30, 40, 0]),
+            Err(PaytableError::NonMonotonic { .. })
+        ));
+    }
+}
@@ -0,0 +1,359 @@
+use super::{Categories, Hand};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, path::Path};
+
+/// Pays for every hand category along with a description of the game.
+///
+/// Paytables are stored as TOML or JSON with the pays keyed by hand name,
+/// ```toml
+/// name = "assignment 3"
+/// bet = 1
+/// cards = 3
+/// notes = "pays are credits returned for the bet"
+///
+/// [pays]
+/// straight_flush = 100
+/// three_of_a_kind = 99
+/// straight = 9
+/// flush = 5
+/// pair = 0
+/// high_card = 0
+/// ```
+/// `cards` is the hand size and may be left out for three card hands. Every
+/// category of that hand size must be listed and better hands may not pay
+/// less than worse ones.
+///
+/// A paytable for a game with its own [`Categories`] lists their names best
+/// first, `categories = ["mini_royal", "straight_flush", ...]`, and keys
+/// its pays by them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "PaytableFile", into = "PaytableFile")]
+pub struct Paytable {
+    pub name: String,
+    /// credits wagered on each game, pays are credits returned for that bet
+    pub bet: usize,
+    /// number of cards in a hand
+    pub cards: usize,
+    pub notes: Option<String>,
+    /// names of the categories paid, best first
+    categories: Vec<String>,
+    /// pays indexed like `categories`
+    pays: Vec<usize>,
+}
+/// Layout of a paytable on disk
+#[derive(Serialize, Deserialize)]
+struct PaytableFile {
+    name: String,
+    #[serde(default = "default_bet")]
+    bet: usize,
+    #[serde(default = "default_cards")]
+    cards: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    /// left out for the standard categories of the hand size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    categories: Option<Vec<String>>,
+    pays: BTreeMap<String, usize>,
+}
+fn default_bet() -> usize {
+    1
+}
+fn default_cards() -> usize {
+    3
+}
+#[derive(Debug)]
+pub enum PaytableError {
+    /// a hand in the file is not one of the category names
+    UnknownHand(String),
+    /// a category has no pay listed
+    MissingHand(String),
+    /// category `better` pays less than `worse`
+    NonMonotonic { better: String, worse: String },
+    /// the bet is zero credits
+    ZeroBet,
+    /// there are no categories for hands of this many cards
+    UnsupportedCards(usize),
+    /// the number of pays does not match the categories of the hand size
+    WrongCount { expected: usize, found: usize },
+    /// the file is not valid TOML or JSON
+    Parse(String),
+    /// the file could not be read
+    Io(std::io::Error),
+}
+impl fmt::Display for PaytableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownHand(name) => write!(f, "unknown hand: {}", name),
+            Self::MissingHand(name) => write!(f, "missing pay for {}", name),
+            Self::NonMonotonic { better, worse } => {
+                write!(f, "{} pays less than {}", better, worse)
+            }
+            Self::ZeroBet => write!(f, "bet must be at least one credit"),
+            Self::UnsupportedCards(cards) => write!(f, "no hands of {} cards", cards),
+            Self::WrongCount { expected, found } => {
+                write!(f, "expected {} pays, found {}", expected, found)
+            }
+            Self::Parse(e) => write!(f, "invalid paytable: {}", e),
+            Self::Io(e) => write!(f, "could not read paytable: {}", e),
+        }
+    }
+}
+impl std::error::Error for PaytableError {}
+impl From<std::io::Error> for PaytableError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl Paytable {
+    /// Builds a three card paytable from pays indexed by `Hand as usize`
+    pub fn new(
+        name: impl Into<String>,
+        pays: impl Into<Vec<usize>>,
+    ) -> Result<Self, PaytableError> {
+        Self::with_cards(name, 3, pays)
+    }
+    /// Builds a paytable for hands of `cards` cards, from pays in the order of
+    /// [`Hand::categories`]
+    pub fn with_cards(
+        name: impl Into<String>,
+        cards: usize,
+        pays: impl Into<Vec<usize>>,
+    ) -> Result<Self, PaytableError> {
+        Self::build(name.into(), cards, standard_names(cards)?, pays.into())
+    }
+    /// Builds a paytable for hands of `cards` cards sorted into
+    /// `categories`, from pays in the order of the categories
+    pub fn for_categories(
+        name: impl Into<String>,
+        cards: usize,
+        categories: &Categories,
+        pays: impl Into<Vec<usize>>,
+    ) -> Result<Self, PaytableError> {
+        let names = categories.names().into_iter().map(String::from).collect();
+        Self::build(name.into(), cards, names, pays.into())
+    }
+    fn build(
+        name: String,
+        cards: usize,
+        categories: Vec<String>,
+        pays: Vec<usize>,
+    ) -> Result<Self, PaytableError> {
+        let paytable = Self {
+            name,
+            bet: 1,
+            cards,
+            notes: None,
+            categories,
+            pays,
+        };
+        paytable.validate()?;
+        Ok(paytable)
+    }
+    pub fn from_toml(s: &str) -> Result<Self, PaytableError> {
+        toml::from_str::<PaytableFile>(s)
+            .map_err(|e| PaytableError::Parse(e.to_string()))?
+            .try_into()
+    }
+    pub fn from_json(s: &str) -> Result<Self, PaytableError> {
+        serde_json::from_str::<PaytableFile>(s)
+            .map_err(|e| PaytableError::Parse(e.to_string()))?
+            .try_into()
+    }
+    /// Loads a `.json` file as JSON and anything else as TOML
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PaytableError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            _ => Self::from_toml(&contents),
+        }
+    }
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("paytables always serialize")
+    }
+    /// Credits paid for a final hand of a standard category.
+    ///
+    /// Panics if the paytable does not pay `hand`.
+    pub fn pay(&self, hand: Hand) -> usize {
+        self.pay_for(hand.name())
+            .unwrap_or_else(|| panic!("{} does not pay {}", self.name, hand.name()))
+    }
+    /// Credits paid for the category called `name`
+    pub fn pay_for(&self, name: &str) -> Option<usize> {
+        self.categories
+            .iter()
+            .position(|category| category == name)
+            .map(|i| self.pays[i])
+    }
+    /// Pays indexed like [`Paytable::categories`]
+    pub fn pays(&self) -> &[usize] {
+        &self.pays
+    }
+    /// Names of the categories paid, best first
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+    /// Whether the paytable pays the standard categories of its hand size
+    pub fn is_standard(&self) -> bool {
+        standard_names(self.cards).is_ok_and(|names| names == self.categories)
+    }
+    fn validate(&self) -> Result<(), PaytableError> {
+        if self.bet == 0 {
+            return Err(PaytableError::ZeroBet);
+        }
+        if !Hand::SIZES.contains(&self.cards) {
+            return Err(PaytableError::UnsupportedCards(self.cards));
+        }
+        if self.pays.len() != self.categories.len() {
+            return Err(PaytableError::WrongCount {
+                expected: self.categories.len(),
+                found: self.pays.len(),
+            });
+        }
+        for i in 1..self.pays.len() {
+            if self.pays[i - 1] < self.pays[i] {
+                return Err(PaytableError::NonMonotonic {
+                    better: self.categories[i - 1].clone(),
+                    worse: self.categories[i].clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+/// Names of the standard categories of hands of `cards` cards
+fn standard_names(cards: usize) -> Result<Vec<String>, PaytableError> {
+    if !Hand::SIZES.contains(&cards) {
+        return Err(PaytableError::UnsupportedCards(cards));
+    }
+    Ok(Hand::categories(cards)
+        .iter()
+        .map(|hand| hand.name().to_string())
+        .collect())
+}
+impl TryFrom<PaytableFile> for Paytable {
+    type Error = PaytableError;
+    fn try_from(file: PaytableFile) -> Result<Self, Self::Error> {
+        let categories = match file.categories {
+            Some(categories) => categories,
+            None => standard_names(file.cards)?,
+        };
+        let mut pays = vec![None; categories.len()];
+        for (name, pay) in file.pays {
+            let index = categories
+                .iter()
+                .position(|category| *category == name)
+                .ok_or(PaytableError::UnknownHand(name))?;
+            pays[index] = Some(pay);
+        }
+        let pays = categories
+            .iter()
+            .zip(pays)
+            .map(|(name, pay)| pay.ok_or_else(|| PaytableError::MissingHand(name.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+        let paytable = Self {
+            name: file.name,
+            bet: file.bet,
+            cards: file.cards,
+            notes: file.notes,
+            categories,
+            pays,
+        };
+        paytable.validate()?;
+        Ok(paytable)
+    }
+}
+impl From<Paytable> for PaytableFile {
+    fn from(paytable: Paytable) -> Self {
+        Self {
+            categories: (!paytable.is_standard()).then(|| paytable.categories.clone()),
+            pays: paytable.categories.into_iter().zip(paytable.pays).collect(),
+            name: paytable.name,
+            bet: paytable.bet,
+            cards: paytable.cards,
+            notes: paytable.notes,
+        }
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    const ASSIGNMENT_3: &str = include_str!("../paytables/assignment3.toml");
+    #[test]
+    fn load_toml() {
+        let paytable = Paytable::from_toml(ASSIGNMENT_3).unwrap();
+        assert_eq!(paytable.name, "assignment 3");
+        assert_eq!(paytable.pays(), &[100, 99, 9, 5, 0, 0]);
+        assert_eq!(paytable.cards, 3);
+        assert_eq!(Paytable::from_toml(&paytable.to_toml()).unwrap(), paytable);
+    }
+    #[test]
+    fn load_json() {
+        let paytable = Paytable::from_json(
+            r#"{"name": "2", "bet": 2, "pays": {"straight_flush": 50, "three_of_a_kind": 25,
+            "straight": 5, "flush": 2, "pair": 0, "high_card": 0}}"#,
+        )
+        .unwrap();
+        assert_eq!(paytable.bet, 2);
+        assert_eq!(paytable.pay(Hand::Straight), 5);
+    }
+    #[test]
+    fn invalid_paytables() {
+        let unknown = ASSIGNMENT_3.replace("high_card", "royal_flush");
+        assert!(matches!(
+            Paytable::from_toml(&unknown),
+            Err(PaytableError::UnknownHand(name)) if name == "royal_flush"
+        ));
+        // two pair needs at least four cards
+        let small = ASSIGNMENT_3.replace("high_card", "two_pair");
+        assert!(matches!(
+            Paytable::from_toml(&small),
+            Err(PaytableError::UnknownHand(name)) if name == "two_pair"
+        ));
+        let missing = ASSIGNMENT_3.replace("pair = 0\n", "");
+        assert!(matches!(
+            Paytable::from_toml(&missing),
+            Err(PaytableError::MissingHand(name)) if name == "pair"
+        ));
+        assert!(matches!(
+            Paytable::new("transposed", [100, 9, 99, 5, 0, 0]),
+            Err(PaytableError::NonMonotonic { better, worse })
+                if better == "three_of_a_kind" && worse == "straight"
+        ));
+    }
+    #[test]
+    fn custom_categories() {
+        let paytable = Paytable::from_toml(
+            r#"name = "mini royal"
+            categories = ["mini_royal", "straight_flush", "three_of_a_kind", "straight",
+                "flush", "pair", "high_card"]
+            [pays]
+            mini_royal = 200
+            straight_flush = 40
+            three_of_a_kind = 30
+            straight = 6
+            flush = 3
+            pair = 1
+            high_card = 0"#,
+        )
+        .unwrap();
+        assert!(!paytable.is_standard());
+        assert_eq!(paytable.pay_for("mini_royal"), Some(200));
+        assert_eq!(paytable.pay(Hand::Flush), 3);
+        assert_eq!(Paytable::from_toml(&paytable.to_toml()).unwrap(), paytable);
+        assert!(Paytable::from_toml(ASSIGNMENT_3).unwrap().is_standard());
+    }
+    #[test]
+    fn five_cards() {
+        let paytable = Paytable::with_cards("five", 5, [50, 25, 9, 6, 4, 3, 2, 1, 0]).unwrap();
+        assert_eq!(paytable.pay(Hand::FullHouse), 9);
+        assert_eq!(Paytable::from_toml(&paytable.to_toml()).unwrap(), paytable);
+        assert!(matches!(
+            Paytable::with_cards("short", 5, [100, 99, 9, 5, 0, 0]),
+            Err(PaytableError::WrongCount {
+                expected: 9,
+                found: 6
+            })
+        ));
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+use super::ParseCardError;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum Rank {
     A = 1,
     Two = 2,
@@ -13,6 +15,9 @@ pub enum Rank {
     Jack = 11,
     Queen = 12,
     King = 13,
+    /// a joker, always wild. Jokers are written `X` and their suit only tells
+    /// the jokers of a deck apart.
+    Joker = 14,
 }
 impl Rank {
     fn from_num(num: usize) -> Option<Self> {
@@ -30,13 +35,56 @@ impl Rank {
             11 => Some(Self::Jack),
             12 => Some(Self::Queen),
             13 => Some(Self::King),
+            14 => Some(Self::Joker),
             _ => None,
         }
     }
 }
+/// Ranks print as `A`, `2`-`9`, `T`, `J`, `Q` and `K`, and jokers as `X`
+impl std::fmt::Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::A => "A",
+            Self::Two => "2",
+            Self::Three => "3",
+            Self::Four => "4",
+            Self::Five => "5",
+            Self::Six => "6",
+            Self::Seven => "7",
+            Self::Eight => "8",
+            Self::Nine => "9",
+            Self::Ten => "T",
+            Self::Jack => "J",
+            Self::Queen => "Q",
+            Self::King => "K",
+            Self::Joker => "X",
+        };
+        f.write_str(name)
+    }
+}
+/// Parses the printed name in either case, ten can also be written `10`
+impl std::str::FromStr for Rank {
+    type Err = ParseCardError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "A" => Ok(Self::A),
+            "T" | "10" => Ok(Self::Ten),
+            "J" => Ok(Self::Jack),
+            "Q" => Ok(Self::Queen),
+            "K" => Ok(Self::King),
+            "X" => Ok(Self::Joker),
+            digit => digit
+                .parse::<usize>()
+                .ok()
+                .filter(|num| (2..=9).contains(num))
+                .and_then(Self::from_num)
+                .ok_or_else(|| ParseCardError::InvalidRank(s.to_string())),
+        }
+    }
+}
 impl PartialOrd for Rank {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        (*self as usize).partial_cmp(&(*other as usize))
+        Some(self.cmp(other))
     }
 }
 impl Ord for Rank {
@@ -45,8 +93,11 @@ impl Ord for Rank {
     }
 }
 impl std::iter::Step for Rank {
-    fn steps_between(start: &Self, end: &Self) -> Option<usize> {
-        Some(*end as usize - *start as usize)
+    fn steps_between(start: &Self, end: &Self) -> (usize, Option<usize>) {
+        match (*end as usize).checked_sub(*start as usize) {
+            Some(steps) => (steps, Some(steps)),
+            None => (0, None),
+        }
     }
     fn forward_checked(start: Self, count: usize) -> Option<Self> {
         let num = (start as usize) + count;
@@ -0,0 +1,45 @@
+use num_rational::Ratio;
+
+/// Exact fraction used for probabilities and returns, only converted to a
+/// float with [`to_f64`] when printing
+pub type Rational = Ratio<i128>;
+
+/// Float approximation of an exact value, for display only
+pub fn to_f64(value: &Rational) -> f64 {
+    *value.numer() as f64 / *value.denom() as f64
+}
+/// Reads a decimal such as `0.995` or `-1.5` as an exact fraction
+pub fn parse_decimal(s: &str) -> Option<Rational> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let numer = format!("{}{}", whole, fraction).parse::<i128>().ok()?;
+    let denom = 10i128.checked_pow(fraction.len() as u32)?;
+    let value = Rational::new(numer, denom);
+    Some(if negative { -value } else { value })
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn decimals() {
+        assert_eq!(parse_decimal("0.99"), Some(Rational::new(99, 100)));
+        assert_eq!(parse_decimal("1"), Some(Rational::from_integer(1)));
+        assert_eq!(parse_decimal("-.5"), Some(Rational::new(-1, 2)));
+        assert_eq!(parse_decimal("1.2.3"), None);
+        assert_eq!(parse_decimal("."), None);
+        assert_eq!(to_f64(&Rational::new(1, 4)), 0.25);
+    }
+}
@@ -0,0 +1,195 @@
+use super::{strategy::held_cards, Card, Categories, Game, HoldAdvice, Paytable, ShuffledDeck};
+use std::fmt;
+
+/// One seat at the draw game: a credit balance and the hand in play. Each
+/// game takes the paytable's bet and deals `N` cards from a [`ShuffledDeck`],
+/// reshuffled after every game, then the player picks the cards to hold,
+/// the discards are replaced from the rest of that deck and the final hand
+/// is paid.
+#[derive(Clone, Debug)]
+pub struct Session<const N: usize = 3> {
+    pub game: Game,
+    pub paytable: Paytable,
+    /// credits left to bet
+    pub credits: usize,
+    /// paytable bets staked on each hand, pays are multiplied by it
+    pub coins: usize,
+    categories: Categories,
+    deck: ShuffledDeck,
+    hand: Option<[Card; N]>,
+}
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionError {
+    /// the balance can not cover the bet
+    InsufficientCredits { credits: usize, bet: usize },
+    /// a hand is already dealt and waiting for the draw
+    HandInPlay,
+    /// there is no hand to draw to
+    NoHand,
+}
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientCredits { credits, bet } => {
+                write!(f, "{} credits can not cover a bet of {}", credits, bet)
+            }
+            Self::HandInPlay => write!(f, "a hand is already in play"),
+            Self::NoHand => write!(f, "no hand has been dealt"),
+        }
+    }
+}
+impl std::error::Error for SessionError {}
+/// How one game played out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round<const N: usize = 3> {
+    pub dealt: [Card; N],
+    /// cards kept, `None` marks a card that was discarded
+    pub hold: [Option<Card>; N],
+    pub cards: [Card; N],
+    /// name of the category of the final hand
+    pub category: String,
+    /// credits returned for the bet, every coin of it
+    pub pay: usize,
+}
+impl<const N: usize> Session<N> {
+    /// Seats a player with `credits` at `game`, shuffling with `seed`.
+    ///
+    /// Panics unless `paytable` pays hands of `N` cards in the game's
+    /// categories and the deck can deal a hand and replace all of it.
+    pub fn new(game: Game, paytable: Paytable, credits: usize, seed: u64) -> Self {
+        assert!(
+            game.deck.len() >= 2 * N,
+            "a deck of {} cards can not deal and replace {} cards",
+            game.deck.len(),
+            N
+        );
+        let categories = game.hand_categories(N);
+        assert_eq!(paytable.cards, N, "paytable is for a different hand size");
+        assert_eq!(
+            paytable.categories(),
+            categories.names(),
+            "paytable is for different categories"
+        );
+        Self {
+            deck: game.deck.shuffled(seed),
+            game,
+            paytable,
+            credits,
+            coins: 1,
+            categories,
+            hand: None,
+        }
+    }
+    /// The hand waiting for the draw, if one is dealt
+    pub fn hand(&self) -> Option<[Card; N]> {
+        self.hand
+    }
+    /// Credits staked on each hand
+    pub fn bet(&self) -> usize {
+        self.paytable.bet * self.coins
+    }
+    /// Takes the bet and deals a hand from a freshly shuffled deck
+    pub fn deal(&mut self) -> Result<[Card; N], SessionError> {
+        if self.hand.is_some() {
+            return Err(SessionError::HandInPlay);
+        }
+        let bet = self.bet();
+        if self.credits < bet {
+            return Err(SessionError::InsufficientCredits {
+                credits: self.credits,
+                bet,
+            });
+        }
+        self.credits -= bet;
+        if !self.deck.dealt().is_empty() {
+            self.deck.shuffle();
+        }
+        let hand = self
+            .deck
+            .deal_hand()
+            .expect("the deck holds enough cards to deal");
+        self.hand = Some(hand);
+        Ok(hand)
+    }
+    /// Prices holding the cards of the hand in play where `hold` is set
+    /// against the optimal hold
+    pub fn advise(&self, hold: [bool; N]) -> Result<HoldAdvice<N>, SessionError> {
+        let hand = self.hand.ok_or(SessionError::NoHand)?;
+        Ok(self.game.hold_cost(hand, hold, &self.paytable))
+    }
+    /// Keeps the cards where `hold` is set, replaces the others from the
+    /// deck and pays the final hand
+    pub fn draw(&mut self, hold: [bool; N]) -> Result<Round<N>, SessionError> {
+        let dealt = self.hand.take().ok_or(SessionError::NoHand)?;
+        let discards = hold.iter().filter(|held| !**held).count();
+        let mut drawn = self
+            .deck
+            .deal(discards)
+            .expect("the deck holds enough cards to draw")
+            .into_iter();
+        let cards = std::array::from_fn(|i| match hold[i] {
+            true => dealt[i],
+            false => drawn.next().expect("a card was drawn for each discard"),
+        });
+        let index = self.categories.classify(&self.game.evaluator, &cards);
+        let pay = self.paytable.pays()[index] * self.coins;
+        self.credits += pay;
+        Ok(Round {
+            dealt,
+            hold: held_cards(&dealt, hold),
+            cards,
+            category: self.paytable.categories()[index].clone(),
+            pay,
+        })
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Rational;
+    fn session(credits: usize, seed: u64) -> Session {
+        let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+        Session::new(Game::standard(), paytable, credits, seed)
+    }
+    #[test]
+    fn credits() {
+        let mut session = session(2, 11);
+        assert_eq!(session.draw([true; 3]), Err(SessionError::NoHand));
+        let hand = session.deal().unwrap();
+        assert_eq!(session.deal(), Err(SessionError::HandInPlay));
+        assert_eq!(session.credits, 1);
+        let round = session.draw([true, false, true]).unwrap();
+        assert_eq!(round.dealt, hand);
+        assert_eq!([round.cards[0], round.cards[2]], [hand[0], hand[2]]);
+        assert_ne!(round.cards[1], hand[1]);
+        assert_eq!(session.credits, 1 + round.pay);
+        let pays = session.paytable.pays();
+        let category = session
+            .paytable
+            .categories()
+            .iter()
+            .position(|c| *c == round.category);
+        assert_eq!(pays[category.unwrap()], round.pay);
+        // the same seed deals the same games
+        let mut again = self::session(2, 11);
+        assert_eq!(again.deal().unwrap(), hand);
+        assert_eq!(again.draw([true, false, true]).unwrap(), round);
+        let mut broke = self::session(0, 11);
+        assert_eq!(
+            broke.deal(),
+            Err(SessionError::InsufficientCredits { credits: 0, bet: 1 })
+        );
+    }
+    #[test]
+    fn advisor() {
+        let mut session = session(10, 3);
+        let hand = session.deal().unwrap();
+        let advice = session.advise([false; 3]).unwrap();
+        let optimal = advice.optimal.hold.map(|card| card.is_some());
+        assert!(advice.cost >= Rational::from_integer(0));
+        let best = session.advise(optimal).unwrap();
+        assert_eq!(best.cost, Rational::from_integer(0));
+        assert_eq!(best.chosen_return, advice.optimal.expected_return);
+        assert_eq!(session.hand(), Some(hand));
+    }
+}
@@ -0,0 +1,359 @@
+use super::{
+    strategy::{best_play, hold_tables, Supersets},
+    Card, Game, Paytable, Rng, Session, SessionError,
+};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// Rule choosing the cards to hold, given the dealt hand
+type HoldRule<const N: usize> = Arc<dyn Fn(&[Card; N]) -> [bool; N] + Send + Sync>;
+/// How a simulated player picks the cards to hold
+#[derive(Clone)]
+pub enum HoldStrategy<const N: usize = 3> {
+    /// the hold with the best expected return
+    Optimal,
+    /// stands pat on every dealt hand
+    HoldEverything,
+    /// holds the cards where the rule returns `true`
+    Custom(HoldRule<N>),
+}
+impl<const N: usize> HoldStrategy<N> {
+    pub fn custom(rule: impl Fn(&[Card; N]) -> [bool; N] + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(rule))
+    }
+}
+impl<const N: usize> fmt::Debug for HoldStrategy<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Optimal => write!(f, "Optimal"),
+            Self::HoldEverything => write!(f, "HoldEverything"),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+/// Monte Carlo runs of playing sessions: every session seats a player with
+/// the same bankroll at a [`Session`] and plays up to `hands` games, stopping
+/// early once the balance can not cover another bet.
+///
+/// ```
+/// use cs411_cards::{Paytable, Simulation};
+/// let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+/// let report = Simulation::<3>::new(paytable, 20, 50, 10, 1).run();
+/// assert_eq!(report.sessions.len(), 10);
+/// assert!(report.risk_of_ruin() <= 1.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Simulation<const N: usize = 3> {
+    pub game: Game,
+    pub paytable: Paytable,
+    /// credits each session starts with
+    pub bankroll: usize,
+    /// paytable bets staked on each hand
+    pub coins: usize,
+    /// most games played in a session
+    pub hands: usize,
+    pub sessions: usize,
+    /// seeds the shuffles of every session, the same seed plays the same games
+    pub seed: u64,
+    pub strategy: HoldStrategy<N>,
+}
+/// How one simulated session ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionResult {
+    pub final_bankroll: usize,
+    /// games played
+    pub hands: usize,
+    /// game after which the balance could not cover another bet
+    pub bust: Option<usize>,
+    /// first game after which the balance was at least twice the bankroll
+    pub doubled: Option<usize>,
+}
+/// Results of a [`Simulation`], one per session in the order played
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulationReport {
+    pub bankroll: usize,
+    pub sessions: Vec<SessionResult>,
+}
+/// Sessions whose final bankroll falls in `low..high`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bin {
+    pub low: usize,
+    pub high: usize,
+    pub sessions: usize,
+}
+impl<const N: usize> Simulation<N> {
+    /// Sessions of the standard game betting one coin a hand, played
+    /// optimally
+    pub fn new(
+        paytable: Paytable,
+        bankroll: usize,
+        hands: usize,
+        sessions: usize,
+        seed: u64,
+    ) -> Self {
+        Self {
+            game: Game::standard(),
+            paytable,
+            bankroll,
+            coins: 1,
+            hands,
+            sessions,
+            seed,
+            strategy: HoldStrategy::Optimal,
+        }
+    }
+    pub fn with_strategy(self, strategy: HoldStrategy<N>) -> Self {
+        Self { strategy, ..self }
+    }
+    /// Plays every session. Optimal holds are solved once per distinct hand
+    /// and shared by the sessions.
+    ///
+    /// Panics unless the paytable pays hands of `N` cards in the game's
+    /// categories.
+    pub fn run(&self) -> SimulationReport {
+        let mut seeds = Rng::new(self.seed);
+        let mut solved = HashMap::new();
+        let mut supersets = match self.strategy {
+            HoldStrategy::Optimal => Some(Supersets::new(&self.game)),
+            _ => None,
+        };
+        let sessions = (0..self.sessions)
+            .map(|_| {
+                let mut session = Session::<N>::new(
+                    self.game.clone(),
+                    self.paytable.clone(),
+                    self.bankroll,
+                    seeds.next_u64(),
+                );
+                session.coins = self.coins;
+                self.play(&mut session, |cards| match &self.strategy {
+                    HoldStrategy::Optimal => {
+                        let supersets = supersets.as_mut().expect("optimal play has supersets");
+                        optimal_hold(cards, &self.paytable, supersets, &mut solved)
+                    }
+                    HoldStrategy::HoldEverything => [true; N],
+                    HoldStrategy::Custom(rule) => rule(cards),
+                })
+            })
+            .collect();
+        SimulationReport {
+            bankroll: self.bankroll,
+            sessions,
+        }
+    }
+    fn play(
+        &self,
+        session: &mut Session<N>,
+        mut hold: impl FnMut(&[Card; N]) -> [bool; N],
+    ) -> SessionResult {
+        let mut result = SessionResult {
+            final_bankroll: session.credits,
+            hands: 0,
+            bust: None,
+            doubled: None,
+        };
+        while result.hands < self.hands {
+            let cards = match session.deal() {
+                Ok(cards) => cards,
+                Err(SessionError::InsufficientCredits { .. }) => {
+                    result.bust = Some(result.hands);
+                    break;
+                }
+                Err(error) => unreachable!("a fresh deal failed: {}", error),
+            };
+            session.draw(hold(&cards)).expect("a hand was just dealt");
+            result.hands += 1;
+            if result.doubled.is_none() && session.credits >= 2 * self.bankroll {
+                result.doubled = Some(result.hands);
+            }
+        }
+        if result.bust.is_none() && session.credits < session.bet() {
+            result.bust = Some(result.hands);
+        }
+        result.final_bankroll = session.credits;
+        result
+    }
+}
+/// Optimal hold of `cards`, solved once for each set of cards
+fn optimal_hold<const N: usize>(
+    cards: &[Card; N],
+    paytable: &Paytable,
+    supersets: &mut Supersets<N>,
+    solved: &mut HashMap<[Card; N], [bool; N]>,
+) -> [bool; N] {
+    let mut order: [usize; N] = std::array::from_fn(|i| i);
+    order.sort_by_key(|i| cards[*i]);
+    let sorted = order.map(|i| cards[i]);
+    let held = *solved.entry(sorted).or_insert_with(|| {
+        let tables = hold_tables(&sorted, supersets);
+        best_play(sorted, &tables, paytable)
+            .hold
+            .map(|card| card.is_some())
+    });
+    let mut hold = [false; N];
+    for (i, position) in order.into_iter().enumerate() {
+        hold[position] = held[i];
+    }
+    hold
+}
+impl SimulationReport {
+    /// Share of the sessions that went bust
+    pub fn risk_of_ruin(&self) -> f64 {
+        self.share(|session| session.bust.is_some())
+    }
+    /// Share of the sessions that doubled the bankroll at some point
+    pub fn double_rate(&self) -> f64 {
+        self.share(|session| session.doubled.is_some())
+    }
+    fn share(&self, test: impl Fn(&SessionResult) -> bool) -> f64 {
+        if self.sessions.is_empty() {
+            return 0.0;
+        }
+        self.sessions.iter().filter(|session| test(session)).count() as f64
+            / self.sessions.len() as f64
+    }
+    /// Mean games played before going bust, over the sessions that did
+    pub fn mean_hands_to_bust(&self) -> Option<f64> {
+        mean(self.sessions.iter().filter_map(|session| session.bust))
+    }
+    /// Mean games played before doubling, over the sessions that did
+    pub fn mean_hands_to_double(&self) -> Option<f64> {
+        mean(self.sessions.iter().filter_map(|session| session.doubled))
+    }
+    /// Final bankrolls, lowest first
+    pub fn final_bankrolls(&self) -> Vec<usize> {
+        let mut bankrolls = self
+            .sessions
+            .iter()
+            .map(|session| session.final_bankroll)
+            .collect::<Vec<_>>();
+        bankrolls.sort();
+        bankrolls
+    }
+    pub fn mean_final_bankroll(&self) -> Option<f64> {
+        mean(self.sessions.iter().map(|session| session.final_bankroll))
+    }
+    /// Final bankroll of the session at `q` of the way up the sorted
+    /// sessions, the nearest rank percentile.
+    ///
+    /// Panics unless `q` is in `0.0..=1.0`.
+    pub fn percentile(&self, q: f64) -> Option<usize> {
+        assert!((0.0..=1.0).contains(&q), "percentile {} out of range", q);
+        let bankrolls = self.final_bankrolls();
+        let rank = (q * bankrolls.len() as f64).ceil() as usize;
+        bankrolls.get(rank.max(1) - 1).copied()
+    }
+    /// Counts the final bankrolls in `bins` equal ranges from zero up to
+    /// past the highest, no ranges when `bins` is zero
+    pub fn histogram(&self, bins: usize) -> Vec<Bin> {
+        if bins == 0 {
+            return vec![];
+        }
+        let top = self.final_bankrolls().last().map_or(0, |high| high + 1);
+        let width = top.div_ceil(bins).max(1);
+        let mut histogram = (0..bins)
+            .map(|i| Bin {
+                low: i * width,
+                high: (i + 1) * width,
+                sessions: 0,
+            })
+            .collect::<Vec<_>>();
+        for session in self.sessions.iter() {
+            histogram[session.final_bankroll / width].sessions += 1;
+        }
+        histogram
+    }
+}
+fn mean(values: impl Iterator<Item = usize>) -> Option<f64> {
+    let (count, sum) = values.fold((0, 0), |(count, sum), value| (count + 1, sum + value));
+    (count > 0).then(|| sum as f64 / count as f64)
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{optimal_play, parse_hand, strategy::held_cards};
+    fn paytable() -> Paytable {
+        // returns 0.85 of the bet when played well
+        Paytable::new("house", [10, 8, 2, 2, 1, 0]).unwrap()
+    }
+    #[test]
+    fn reproducible() {
+        let simulation = Simulation::<3>::new(paytable(), 10, 100, 50, 9);
+        let report = simulation.run();
+        assert_eq!(report, simulation.run());
+        assert_ne!(
+            report,
+            Simulation {
+                seed: 10,
+                ..simulation
+            }
+            .run()
+        );
+        assert_eq!(report.sessions.len(), 50);
+        for session in report.sessions.iter() {
+            assert!(session.hands <= 100);
+            match session.bust {
+                Some(hands) => {
+                    assert_eq!(hands, session.hands);
+                    assert_eq!(session.final_bankroll, 0);
+                }
+                None => assert_eq!(session.hands, 100),
+            }
+            if let Some(hands) = session.doubled {
+                assert!(hands <= session.hands);
+            }
+        }
+        let sessions = report
+            .histogram(4)
+            .iter()
+            .map(|bin| bin.sessions)
+            .sum::<usize>();
+        assert_eq!(sessions, 50);
+        assert_eq!(report.histogram(0), []);
+        assert!(report.percentile(0.0) <= report.percentile(0.5));
+        assert_eq!(
+            report.percentile(1.0),
+            report.final_bankrolls().last().copied()
+        );
+    }
+    #[test]
+    fn strategies() {
+        let simulation = Simulation::<3>::new(paytable(), 5, 200, 200, 4);
+        let optimal = simulation.run();
+        let pat = simulation
+            .clone()
+            .with_strategy(HoldStrategy::HoldEverything)
+            .run();
+        // standing pat on every deal seldom makes a paying hand
+        assert!(optimal.risk_of_ruin() < pat.risk_of_ruin());
+        assert!(pat.mean_hands_to_bust().is_some());
+        let rule = simulation
+            .clone()
+            .with_strategy(HoldStrategy::custom(|_| [true; 3]))
+            .run();
+        assert_eq!(rule, pat);
+        // a paytable returning every bet never moves the bankroll
+        let flat = Paytable::new("flat", [1, 1, 1, 1, 1, 1]).unwrap();
+        let report = Simulation::<3>::new(flat, 3, 20, 5, 0).run();
+        assert_eq!(report.final_bankrolls(), [3; 5]);
+        assert_eq!(report.risk_of_ruin(), 0.0);
+        assert_eq!(report.mean_hands_to_double(), None);
+    }
+    #[test]
+    fn cached_holds() {
+        let game = Game::standard();
+        let paytable = paytable();
+        let mut supersets = Supersets::new(&game);
+        let mut solved = HashMap::new();
+        for hand in ["Kd 7h 2c", "2c Kd 7h", "Qs 9s Jh", "4d 4c As", "Tc Jc Qc"] {
+            let cards = parse_hand::<3>(hand).unwrap();
+            let hold = optimal_hold(&cards, &paytable, &mut supersets, &mut solved);
+            let play = optimal_play(cards, &paytable);
+            assert_eq!(
+                play.hold_return(&held_cards(&cards, hold)),
+                Some(play.expected_return)
+            );
+        }
+        // the same cards in another order are solved once
+        assert_eq!(solved.len(), 4);
+    }
+}
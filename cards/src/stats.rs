@@ -0,0 +1,118 @@
+use super::{to_f64, Rational};
+
+/// Distribution of the return of a single game under optimal play, per
+/// credit bet. Ties between holds go to the hold the solver picks, keeping
+/// more cards, which can change the variance but never the mean.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameStatistics {
+    /// probability of finishing with each hand, indexed by `Hand::index`
+    pub hand_probabilities: Vec<Rational>,
+    pub expected_return: Rational,
+    pub variance: Rational,
+    /// probability that the final hand pays anything
+    pub hit_frequency: Rational,
+}
+impl GameStatistics {
+    /// Builds the statistics from the probability of every final hand and
+    /// the pays, per credit bet, for each hand
+    pub fn new(hand_probabilities: Vec<Rational>, pays: &[Rational]) -> Self {
+        let zero = Rational::from_integer(0);
+        let mut expected_return = zero;
+        let mut second_moment = zero;
+        let mut hit_frequency = zero;
+        for (prob, pay) in hand_probabilities.iter().zip(pays.iter()) {
+            expected_return += prob * pay;
+            second_moment += prob * pay * pay;
+            if *pay > zero {
+                hit_frequency += prob;
+            }
+        }
+        Self {
+            hand_probabilities,
+            expected_return,
+            variance: second_moment - expected_return * expected_return,
+            hit_frequency,
+        }
+    }
+    pub fn standard_deviation(&self) -> f64 {
+        to_f64(&self.variance).sqrt()
+    }
+    /// Standard deviation scaled by the two sided normal quantile for
+    /// `confidence`, e.g. `0.9` for the usual 90% volatility index
+    pub fn volatility_index(&self, confidence: f64) -> f64 {
+        inverse_normal((1.0 + confidence) / 2.0) * self.standard_deviation()
+    }
+}
+/// Quantile of the standard normal distribution, using Acklam's rational
+/// approximation which has a relative error below 1.2e-9
+pub fn inverse_normal(p: f64) -> f64 {
+    assert!(0.0 < p && p < 1.0, "probability must be in (0, 1)");
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const LOW: f64 = 0.02425;
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn normal_quantiles() {
+        assert!((inverse_normal(0.95) - 1.644854).abs() < 1e-6);
+        assert!((inverse_normal(0.975) - 1.959964).abs() < 1e-6);
+        assert!((inverse_normal(0.005) + 2.575829).abs() < 1e-6);
+        assert_eq!(inverse_normal(0.5), 0.0);
+    }
+    #[test]
+    fn coin_flip() {
+        let half = Rational::new(1, 2);
+        let zero = Rational::from_integer(0);
+        let stats = GameStatistics::new(
+            vec![half, half, zero, zero, zero, zero],
+            &[Rational::from_integer(2), zero, zero, zero, zero, zero],
+        );
+        assert_eq!(stats.expected_return, Rational::from_integer(1));
+        assert_eq!(stats.variance, Rational::from_integer(1));
+        assert_eq!(stats.hit_frequency, half);
+        assert!((stats.volatility_index(0.9) - 1.644854).abs() < 1e-6);
+    }
+}
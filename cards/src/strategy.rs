@@ -0,0 +1,366 @@
+use super::{
+    deal::for_each_deal, Card, Categories, Evaluator, Game, HandTable, Paytable, Rational,
+};
+use std::collections::HashMap;
+
+/// Result of solving a dealt hand: the best hold and the expected return of
+/// every hold that was considered.
+#[derive(Clone, Debug)]
+pub struct Play<const N: usize = 3> {
+    /// cards to keep, `None` marks a card that is discarded and redrawn
+    pub hold: [Option<Card>; N],
+    /// expected credits returned for the paytable's whole bet, not divided
+    /// by it as [`calculate_expected_return`] is
+    pub expected_return: Rational,
+    /// every hold pattern with its expected return, including the best one,
+    /// leaving out holds whose discards the deck can not replace
+    pub holds: Vec<([Option<Card>; N], Rational)>,
+}
+impl<const N: usize> Play<N> {
+    /// Expected return of `hold`, `None` unless it keeps cards of the dealt
+    /// hand in their places
+    pub fn hold_return(&self, hold: &[Option<Card>; N]) -> Option<Rational> {
+        self.holds
+            .iter()
+            .find(|(other, _)| other == hold)
+            .map(|(_, expected_return)| *expected_return)
+    }
+    /// Every hold, best first. Holds that return the same keep the solver's
+    /// order, more cards kept first.
+    pub fn ranked_holds(&self) -> Vec<([Option<Card>; N], Rational)> {
+        let mut holds = self.holds.clone();
+        holds.sort_by_key(|(_, expected_return)| std::cmp::Reverse(*expected_return));
+        holds
+    }
+}
+/// What a chosen hold costs against the optimal play of the same hand
+#[derive(Clone, Debug)]
+pub struct HoldAdvice<const N: usize = 3> {
+    pub optimal: Play<N>,
+    /// cards kept by the chosen hold, `None` marks a discard
+    pub hold: [Option<Card>; N],
+    /// expected credits returned by the chosen hold
+    pub chosen_return: Rational,
+    /// expected credits given up against the optimal hold, zero when the
+    /// choice is optimal
+    pub cost: Rational,
+    /// place of the chosen hold among every hold, 1 for the best, holds
+    /// returning the same share the better place
+    pub rank: usize,
+}
+impl<const N: usize> HoldAdvice<N> {
+    pub(crate) fn new(optimal: Play<N>, hold: [Option<Card>; N]) -> Self {
+        let chosen_return = optimal
+            .hold_return(&hold)
+            .expect("the solver prices every hold");
+        let rank = 1 + optimal
+            .holds
+            .iter()
+            .filter(|(_, expected_return)| *expected_return > chosen_return)
+            .count();
+        Self {
+            cost: optimal.expected_return - chosen_return,
+            optimal,
+            hold,
+            chosen_return,
+            rank,
+        }
+    }
+}
+/// Hold patterns of `N` cards as bitmasks where bit `i` set means card `i` is
+/// kept. Ordered so that holds keeping more cards come first, on a tie the
+/// solver keeps the earlier hold.
+pub(crate) fn hold_masks<const N: usize>() -> Vec<usize> {
+    let mut masks = (0..1 << N).collect::<Vec<usize>>();
+    masks.sort_by_key(|mask| (N as u32 - mask.count_ones(), *mask));
+    masks
+}
+/// Bitmask of the cards a hold keeps
+pub(crate) fn hold_mask<const N: usize>(hold: &[Option<Card>; N]) -> usize {
+    (0..N)
+        .filter(|i| hold[*i].is_some())
+        .fold(0, |mask, i| mask | 1 << i)
+}
+fn apply_hold<const N: usize>(cards: &[Card; N], mask: usize) -> [Option<Card>; N] {
+    std::array::from_fn(|i| (mask & (1 << i) != 0).then_some(cards[i]))
+}
+/// The cards kept where `hold` is set
+pub(crate) fn held_cards<const N: usize>(cards: &[Card; N], hold: [bool; N]) -> [Option<Card>; N] {
+    std::array::from_fn(|i| hold[i].then_some(cards[i]))
+}
+/// Tables of the `N` card hands that contain a given set of cards. Tables
+/// of fewer than `N` cards are kept once built: the deals of a game share
+/// most of their subsets, so each set is only completed once.
+pub(crate) struct Supersets<const N: usize = 3> {
+    /// distinct cards of the deck with their number of copies
+    copies: Vec<(Card, usize)>,
+    evaluator: Evaluator,
+    categories: Categories,
+    /// tables by their sorted set of cards
+    tables: HashMap<Vec<Card>, HandTable<N>>,
+}
+impl<const N: usize> Supersets<N> {
+    pub(crate) fn new(game: &Game) -> Self {
+        Self {
+            copies: game.deck.copies(),
+            evaluator: game.evaluator,
+            categories: game.hand_categories(N),
+            tables: HashMap::new(),
+        }
+    }
+    /// hands containing every card in `cards`
+    fn table(&mut self, cards: &[Card]) -> HandTable<N> {
+        let (copies, evaluator, categories) = (&self.copies, &self.evaluator, &self.categories);
+        // a whole hand is its own only completion, not worth keeping
+        if cards.len() == N {
+            return Self::completions(copies, evaluator, categories, cards);
+        }
+        let mut key = cards.to_vec();
+        key.sort_unstable();
+        self.tables
+            .entry(key)
+            .or_insert_with(|| Self::completions(copies, evaluator, categories, cards))
+            .clone()
+    }
+    /// Counts every hand made by adding cards from `copies` to `held`. Each
+    /// held card takes one copy out of the deck, the other copies can still
+    /// be drawn.
+    fn completions(
+        copies: &[(Card, usize)],
+        evaluator: &Evaluator,
+        categories: &Categories,
+        held: &[Card],
+    ) -> HandTable<N> {
+        let rest = copies
+            .iter()
+            .map(|(card, n)| (*card, n - held.iter().filter(|c| *c == card).count()))
+            .filter(|(_, n)| *n > 0)
+            .collect::<Vec<_>>();
+        let mut table = HandTable::for_categories(categories);
+        for_each_deal(&rest, N - held.len(), |drawn, ways| {
+            let cards: [Card; N] = std::array::from_fn(|i| {
+                if i < held.len() {
+                    held[i]
+                } else {
+                    drawn[i - held.len()]
+                }
+            });
+            table.push_index(categories.classify(evaluator, &cards), ways)
+        });
+        table
+    }
+}
+/// Counts the final hands for every hold mask of `cards`, indexed by mask.
+/// Discarded cards are not redrawn, so the hands reachable from a hold are
+/// exactly the hands that contain the held cards and none of the discarded
+/// ones, which inclusion exclusion gets from the superset tables without
+/// enumerating any draws.
+pub(crate) fn hold_tables<const N: usize>(
+    cards: &[Card; N],
+    supersets: &mut Supersets<N>,
+) -> Vec<HandTable<N>> {
+    let all = (1 << N) - 1;
+    let containing = (0..=all)
+        .map(|mask| {
+            let held = (0..N)
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| cards[i])
+                .collect::<Vec<_>>();
+            supersets.table(&held)
+        })
+        .collect::<Vec<_>>();
+    (0..=all)
+        .map(|hold| {
+            let discarded = !hold & all;
+            let mut added = containing[0].emptied();
+            let mut removed = added.clone();
+            for extra in 0..=all {
+                if extra & !discarded != 0 {
+                    continue;
+                }
+                let table = containing[hold | extra].clone();
+                if extra.count_ones().is_multiple_of(2) {
+                    added = added + table;
+                } else {
+                    removed = removed + table;
+                }
+            }
+            added - removed
+        })
+        .collect()
+}
+/// Prices every hold of `cards` from its final hand counts, `tables` is
+/// indexed by hold mask
+pub(crate) fn best_play<const N: usize>(
+    cards: [Card; N],
+    tables: &[HandTable<N>],
+    paytable: &Paytable,
+) -> Play<N> {
+    assert_eq!(paytable.cards, N, "paytable is for a different hand size");
+    // a deck too small to replace the discards leaves some holds no draws,
+    // keeping every card always has its one
+    let holds = hold_masks::<N>()
+        .into_iter()
+        .filter(|mask| tables[*mask].total() > 0)
+        .map(|mask| {
+            let pay = tables[mask].calculate_return_full(paytable);
+            (apply_hold(&cards, mask), pay)
+        })
+        .collect::<Vec<_>>();
+    let (hold, expected_return) =
+        holds.iter().fold(
+            holds[0],
+            |best, hold| {
+                if hold.1 > best.1 {
+                    *hold
+                } else {
+                    best
+                }
+            },
+        );
+    Play {
+        hold,
+        expected_return,
+        holds,
+    }
+}
+/// Solves one dealt hand of `game`
+pub(crate) fn solve<const N: usize>(game: &Game, cards: [Card; N], paytable: &Paytable) -> Play<N> {
+    assert_eq!(
+        paytable.categories(),
+        game.hand_categories(N).names(),
+        "paytable is for different categories"
+    );
+    let tables = hold_tables(&cards, &mut Supersets::new(game));
+    best_play(cards, &tables, paytable)
+}
+/// calculates optimal play based on card, every hold/discard pattern is
+/// priced by exact counts of the possible draws
+pub fn optimal_play<const N: usize>(cards: [Card; N], paytable: &Paytable) -> Play<N> {
+    Game::standard().optimal_play(cards, paytable)
+}
+/// Expected return of keeping the cards of `cards` where `hold` is set,
+/// against the optimal hold, along with how the hold ranks among all of them
+pub fn hold_cost<const N: usize>(
+    cards: [Card; N],
+    hold: [bool; N],
+    paytable: &Paytable,
+) -> HoldAdvice<N> {
+    Game::standard().hold_cost(cards, hold, paytable)
+}
+/// Expected return per credit bet when every deal of the paytable's hand
+/// size is played optimally. Builds [`GameOutcomes`](crate::GameOutcomes)
+/// for a single paytable, keep the outcomes around instead when evaluating
+/// several.
+pub fn calculate_expected_return(paytable: &Paytable) -> Rational {
+    Game::standard().expected_return(paytable)
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{get_hand, parse_hand, Deck};
+    /// Fills the `positions` of `hand` with every combination of cards from
+    /// `deck[start..]` and records the finished hands in `table`.
+    fn push_draws(
+        hand: &mut [Card; 3],
+        positions: &[usize],
+        deck: &[Card],
+        start: usize,
+        table: &mut HandTable,
+    ) {
+        match positions.split_first() {
+            None => table.push_index(get_hand(hand).index(3), 1),
+            Some((pos, rest)) => {
+                for i in start..deck.len() {
+                    hand[*pos] = deck[i];
+                    push_draws(hand, rest, deck, i + 1, table);
+                }
+            }
+        }
+    }
+    #[test]
+    fn hold_tables_match_enumeration() {
+        // jokers resolve in `get_hand` too, so the enumeration covers
+        // them, and every copy in the shoe is drawn on its own
+        let game = Game::from_deck(Deck::shoe(2).with_jokers(1));
+        let deck = game.deck.cards();
+        let mut supersets = Supersets::new(&game);
+        for cards in [
+            [deck[0], deck[1], deck[2]],
+            [deck[0], deck[40], deck[103]],
+            [deck[14], deck[50], deck[104]],
+        ] {
+            let mut rest = deck.to_vec();
+            for card in cards {
+                let copy = rest.iter().position(|c| *c == card).unwrap();
+                rest.remove(copy);
+            }
+            let tables = hold_tables(&cards, &mut supersets);
+            assert_eq!(tables.len(), 8);
+            for (mask, table) in tables.iter().enumerate() {
+                let positions = (0..3).filter(|i| mask & (1 << i) == 0).collect::<Vec<_>>();
+                let mut expected = HandTable::default();
+                push_draws(&mut cards.clone(), &positions, &rest, 0, &mut expected);
+                assert_eq!(*table, expected);
+            }
+        }
+    }
+    #[test]
+    fn t_optimal_play() {
+        let hand = parse_hand::<3>("8c 9c Tc").unwrap();
+        let paytable = Paytable::new("straight flush", [1, 0, 0, 0, 0, 0]).unwrap();
+        let best_hand = optimal_play(hand, &paytable);
+        assert_eq!(best_hand.expected_return, Rational::from_integer(1));
+        assert_eq!(best_hand.hold, hand.map(Some));
+    }
+    #[test]
+    fn optimal_play_draws_two() {
+        let hand = parse_hand::<3>("2c 7h Kd").unwrap();
+        // only straight flushes and three of a kind pay, keeping the seven
+        // and drawing two makes 3 straight flushes and 3 trips out of C(49, 2)
+        let paytable = Paytable::new("trips", [1176, 1176, 0, 0, 0, 0]).unwrap();
+        let play = optimal_play(hand, &paytable);
+        assert_eq!(play.holds.len(), 8);
+        assert_eq!(play.hold, [None, Some(hand[1]), None]);
+        assert_eq!(play.expected_return, Rational::from_integer(6));
+    }
+    #[test]
+    fn optimal_play_four_cards() {
+        let hand = parse_hand::<4>("Ac Ad Ah 2c").unwrap();
+        let paytable = Paytable::with_cards("quads", 4, [48, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+        let play = optimal_play(hand, &paytable);
+        assert_eq!(play.holds.len(), 16);
+        // one ace left among the 48 unseen cards
+        assert_eq!(
+            play.hold,
+            [Some(hand[0]), Some(hand[1]), Some(hand[2]), None]
+        );
+        assert_eq!(play.expected_return, Rational::from_integer(1));
+    }
+    #[test]
+    fn mistakes() {
+        let hand = parse_hand::<3>("2c 7h Kd").unwrap();
+        let paytable = Paytable::new("trips", [1176, 1176, 0, 0, 0, 0]).unwrap();
+        let best = hold_cost(hand, [false, true, false], &paytable);
+        assert_eq!(best.cost, Rational::from_integer(0));
+        assert_eq!(best.rank, 1);
+        // the deuce and king draw alike, the 7 makes more straight flushes
+        let mistake = hold_cost(hand, [true, false, false], &paytable);
+        assert_eq!(mistake.chosen_return, Rational::new(1176 * 5, 1176));
+        assert_eq!(mistake.cost, Rational::from_integer(1));
+        // drawing three cards beats it too
+        assert_eq!(mistake.rank, 3);
+        let ranked = mistake.optimal.ranked_holds();
+        assert_eq!(ranked.len(), 8);
+        assert_eq!(ranked[0].0, [None, Some(hand[1]), None]);
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+    #[test]
+    fn expected_return() {
+        let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+        assert_eq!(
+            calculate_expected_return(&paytable),
+            Rational::new(9219467, 2993900)
+        );
+    }
+}
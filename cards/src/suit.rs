@@ -1,4 +1,6 @@
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+use super::ParseCardError;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum Suit {
     Clubs = 0,
     Hearts = 1,
@@ -6,6 +8,7 @@ pub enum Suit {
     Diamonds = 3,
 }
 impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Hearts, Suit::Spades, Suit::Diamonds];
     fn from_num(num: usize) -> Option<Self> {
         match num {
             0 => Some(Self::Clubs),
@@ -16,14 +19,52 @@ impl Suit {
         }
     }
 }
+/// Suits print as `c`, `h`, `s` and `d`, or as `♣`, `♥`, `♠` and `♦` with
+/// the alternate flag (`{:#}`)
+impl std::fmt::Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match (self, f.alternate()) {
+            (Self::Clubs, false) => "c",
+            (Self::Hearts, false) => "h",
+            (Self::Spades, false) => "s",
+            (Self::Diamonds, false) => "d",
+            (Self::Clubs, true) => "♣",
+            (Self::Hearts, true) => "♥",
+            (Self::Spades, true) => "♠",
+            (Self::Diamonds, true) => "♦",
+        };
+        f.write_str(name)
+    }
+}
+/// Parses a suit letter in either case or a filled or outlined suit glyph
+impl std::str::FromStr for Suit {
+    type Err = ParseCardError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "c" | "C" | "♣" | "♧" => Ok(Self::Clubs),
+            "h" | "H" | "♥" | "♡" => Ok(Self::Hearts),
+            "s" | "S" | "♠" | "♤" => Ok(Self::Spades),
+            "d" | "D" | "♦" | "♢" => Ok(Self::Diamonds),
+            _ => Err(ParseCardError::InvalidSuit(s.to_string())),
+        }
+    }
+}
 impl PartialOrd for Suit {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        (*self as usize).partial_cmp(&(*other as usize))
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Suit {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (*self as usize).cmp(&(*other as usize))
     }
 }
 impl std::iter::Step for Suit {
-    fn steps_between(start: &Self, end: &Self) -> Option<usize> {
-        Some(*end as usize - *start as usize)
+    fn steps_between(start: &Self, end: &Self) -> (usize, Option<usize>) {
+        match (*end as usize).checked_sub(*start as usize) {
+            Some(steps) => (steps, Some(steps)),
+            None => (0, None),
+        }
     }
     fn forward_checked(start: Self, count: usize) -> Option<Self> {
         let num = (start as usize) + count;
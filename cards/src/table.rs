@@ -0,0 +1,238 @@
+use super::{deal::for_each_dealt_hand, Card, Categories, Game, Hand, Paytable, Rational};
+use std::sync::Arc;
+
+/// Partially filled paytable used while searching for pays, in the order of
+/// [`Hand::categories`], `None` marks a pay that has not been chosen yet
+#[derive(Clone, Debug)]
+pub struct HandPayGuess {
+    pub pay: Vec<Option<usize>>,
+}
+/// Number of times each hand category of `N` card hands was seen. Tables
+/// hold the standard [`Hand::categories`] unless built for a game with its
+/// own [`Categories`], and keep the names of the categories they count.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandTable<const N: usize = 3> {
+    /// names of the categories, best first, shared by the tables of a game
+    names: Arc<[String]>,
+    /// counts indexed by position in the category list
+    hands: Vec<usize>,
+}
+impl<const N: usize> std::ops::Add for HandTable<N> {
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        debug_assert_eq!(self.names, other.names, "tables of different categories");
+        for i in 0..self.hands.len() {
+            self.hands[i] += other.hands[i]
+        }
+        self
+    }
+}
+impl<const N: usize> std::ops::Sub for HandTable<N> {
+    type Output = Self;
+    fn sub(mut self, other: Self) -> Self {
+        debug_assert_eq!(self.names, other.names, "tables of different categories");
+        for i in 0..self.hands.len() {
+            self.hands[i] -= other.hands[i]
+        }
+        self
+    }
+}
+impl<const N: usize> HandTable<N> {
+    /// Counts every unordered deal of `N` cards from a fresh deck
+    pub fn all_deals() -> Self {
+        Self::for_game(&Game::standard())
+    }
+    /// Empty table of `categories`
+    pub fn for_categories(categories: &Categories) -> Self {
+        Self {
+            names: categories.names().into_iter().map(String::from).collect(),
+            hands: vec![0; categories.len()],
+        }
+    }
+    /// Empty table of the same categories
+    pub(crate) fn emptied(&self) -> Self {
+        Self {
+            names: self.names.clone(),
+            hands: vec![0; self.hands.len()],
+        }
+    }
+    /// Counts every unordered deal of `N` cards from the game's deck, sorted
+    /// into the game's categories
+    pub fn for_game(game: &Game) -> Self {
+        let categories = game.hand_categories(N);
+        let mut table = HandTable::for_categories(&categories);
+        for_each_dealt_hand(&game.deck.copies(), |cards: [Card; N], ways| {
+            table.push_index(categories.classify(&game.evaluator, &cards), ways)
+        });
+        table
+    }
+    /// Records `weight` hands of the category at `index` in the list
+    pub fn push_index(&mut self, index: usize, weight: usize) {
+        self.hands[index] += weight;
+    }
+    /// Number of times the category named after `hand` was seen, `None` if
+    /// the table's categories leave it out
+    pub fn count(&self, hand: Hand) -> Option<usize> {
+        let index = self.names.iter().position(|name| name == hand.name())?;
+        Some(self.hands[index])
+    }
+    /// Names of the categories, best first
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+    /// Counts indexed by position in the category list
+    pub fn counts(&self) -> &[usize] {
+        &self.hands
+    }
+    /// Number of hands seen in total
+    pub fn total(&self) -> usize {
+        self.hands.iter().sum()
+    }
+    /// Expected return of the partial paytable, unset pays count as zero
+    pub fn calculate_return(&self, guess: &HandPayGuess) -> Rational {
+        let pay: usize = self
+            .hands
+            .iter()
+            .zip(guess.pay.iter())
+            .map(|(n, pay)| n * pay.unwrap_or(0))
+            .sum();
+        Rational::new(pay as i128, self.total() as i128)
+    }
+    /// Expected pay of the hands in the table.
+    ///
+    /// Panics if the paytable pays a different number of categories.
+    pub fn calculate_return_full(&self, paytable: &Paytable) -> Rational {
+        assert_eq!(
+            paytable.pays().len(),
+            self.hands.len(),
+            "paytable is for different categories"
+        );
+        Rational::new(
+            self.total_pay(paytable.pays()) as i128,
+            self.total() as i128,
+        )
+    }
+    /// Sum of the pays of every hand in the table, `pays` is indexed like
+    /// the counts
+    pub fn total_pay(&self, pays: &[usize]) -> usize {
+        self.hands
+            .iter()
+            .zip(pays.iter())
+            .map(|(n, pay)| n * pay)
+            .sum()
+    }
+    /// Uses brute force to find the pay table that fits inside
+    /// of the given range with the pays in decending order,
+    /// assumes first pay is filled out and lays out unfilled pays in decending order
+    pub fn build_paytable(
+        &self,
+        guess: HandPayGuess,
+        low_return: Rational,
+        high_return: Rational,
+    ) -> Option<HandPayGuess> {
+        let mut check_idx = guess
+            .pay
+            .iter()
+            .enumerate()
+            .filter(|(_i, pay)| pay.is_none())
+            .map(|(i, _pay)| i);
+        let expected_pay = self.calculate_return(&guess);
+        if expected_pay > high_return {
+            None
+        } else if expected_pay > low_return {
+            Some(guess)
+        } else if let Some(idx) = check_idx.next() {
+            let mut guesses = (0..guess.pay[idx - 1].unwrap())
+                .rev()
+                .filter_map(|guess_pay| {
+                    let mut guess = guess.clone();
+                    guess.pay[idx] = Some(guess_pay);
+                    self.build_paytable(guess, low_return, high_return)
+                });
+            guesses.next()
+        } else {
+            None
+        }
+    }
+}
+impl<const N: usize> std::fmt::Display for HandTable<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:^20}|{:>8}", "Hand", "n")?;
+        writeln!(f, "------------------------------")?;
+        for (name, num) in self.names.iter().zip(self.hands.iter()) {
+            writeln!(f, "{:^20}|{:>8}", name, num)?;
+        }
+        Ok(())
+    }
+}
+impl<const N: usize> Default for HandTable<N> {
+    /// Empty table of the standard categories
+    fn default() -> Self {
+        Self::for_categories(&Categories::standard(N))
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn all_deals() {
+        let table = HandTable::<3>::all_deals();
+        assert_eq!(table.total(), 22100);
+        assert_eq!(table.count(Hand::StraightFlush), Some(48));
+        assert_eq!(table.count(Hand::ThreeOfAKind), Some(52));
+        assert_eq!(table.count(Hand::Straight), Some(720));
+        assert_eq!(table.count(Hand::Flush), Some(1096));
+        assert_eq!(table.count(Hand::Pair), Some(3744));
+    }
+    #[test]
+    fn custom_categories() {
+        let categories =
+            Categories::standard(3).with(crate::Category::new("mini_royal", 650, |cards| {
+                let mut ranks = cards.iter().map(|card| card.rank).collect::<Vec<_>>();
+                ranks.sort();
+                ranks == [crate::Rank::A, crate::Rank::Queen, crate::Rank::King]
+                    && cards.iter().all(|card| card.suit == cards[0].suit)
+            }));
+        let table = Game::standard()
+            .with_categories(categories)
+            .all_deals::<3>();
+        assert_eq!(table.names()[0], "mini_royal");
+        // the mini royals come out of the straight flushes
+        assert_eq!(table.count(Hand::StraightFlush), Some(44));
+        assert_eq!(table.count(Hand::FourOfAKind), None);
+        let printed = table.to_string();
+        assert_eq!(printed.lines().count(), 2 + 7);
+        assert!(printed.lines().nth(2).unwrap().contains("mini_royal"));
+        assert!(printed.lines().nth(3).unwrap().contains("straight_flush"));
+    }
+    #[test]
+    fn exact_return() {
+        let paytable = Paytable::new("assignment 3", [100, 99, 9, 5, 0, 0]).unwrap();
+        assert_eq!(
+            HandTable::<3>::all_deals().calculate_return_full(&paytable),
+            Rational::new(21908, 22100)
+        );
+    }
+    #[test]
+    fn larger_hands() {
+        let table = HandTable::<4>::all_deals();
+        assert_eq!(table.total(), 270725);
+        assert_eq!(table.count(Hand::FourOfAKind), Some(13));
+        assert_eq!(table.count(Hand::StraightFlush), Some(44));
+        assert_eq!(table.count(Hand::ThreeOfAKind), Some(2496));
+        assert_eq!(table.count(Hand::Flush), Some(2816));
+        assert_eq!(table.count(Hand::Straight), Some(2772));
+        assert_eq!(table.count(Hand::TwoPair), Some(2808));
+        assert_eq!(table.count(Hand::Pair), Some(82368));
+        let table = HandTable::<5>::all_deals();
+        assert_eq!(table.total(), 2598960);
+        assert_eq!(table.count(Hand::StraightFlush), Some(40));
+        assert_eq!(table.count(Hand::FourOfAKind), Some(624));
+        assert_eq!(table.count(Hand::FullHouse), Some(3744));
+        assert_eq!(table.count(Hand::Flush), Some(5108));
+        assert_eq!(table.count(Hand::Straight), Some(10200));
+        assert_eq!(table.count(Hand::ThreeOfAKind), Some(54912));
+        assert_eq!(table.count(Hand::TwoPair), Some(123552));
+        assert_eq!(table.count(Hand::Pair), Some(1098240));
+    }
+}
@@ -0,0 +1,351 @@
+use super::{
+    deal::for_each_combination, deal_classes, generate_deck, strategy::hold_masks, Card,
+    Categories, Category, DealClass, Evaluator, Game, Paytable, PaytableError, Play, Rank,
+    Rational,
+};
+use std::{collections::HashMap, path::Path};
+
+/// A Jacks or Better paytable: the five card
+/// [`Hand::categories`](crate::Hand::categories) with a `royal_flush` above
+/// straight flushes and a `jacks_or_better` pair above the other pairs.
+/// Paytable files list these categories and are validated as any other.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JacksOrBetter {
+    paytable: Paytable,
+}
+impl JacksOrBetter {
+    /// Pays in the order of [`JacksOrBetter::categories`], royal flush
+    /// first, validated as any [`Paytable`] is
+    pub fn new(
+        name: impl Into<String>,
+        pays: impl Into<Vec<usize>>,
+    ) -> Result<Self, PaytableError> {
+        let paytable = Paytable::for_categories(name, 5, &Self::categories(), pays)?;
+        Ok(Self { paytable })
+    }
+    /// The full pay 9/6 game, with the royal flush paying 800 for one as it
+    /// does at the five credit maximum bet
+    pub fn nine_six() -> Self {
+        Self::new("9/6", [800, 50, 25, 9, 6, 4, 3, 2, 1, 0, 0]).expect("9/6 pays every category")
+    }
+    /// Takes the pays of a paytable that lists the Jacks or Better
+    /// categories, in any order
+    pub fn from_paytable(paytable: Paytable) -> Result<Self, PaytableError> {
+        let categories = Self::categories();
+        if let Some(name) = paytable
+            .categories()
+            .iter()
+            .find(|name| categories.position(name).is_none())
+        {
+            return Err(PaytableError::UnknownHand(name.clone()));
+        }
+        let pays = categories
+            .names()
+            .into_iter()
+            .map(|name| {
+                paytable
+                    .pay_for(name)
+                    .ok_or_else(|| PaytableError::MissingHand(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if paytable.cards != 5 {
+            return Err(PaytableError::UnsupportedCards(paytable.cards));
+        }
+        let mut jacks = Self::new(paytable.name, pays)?;
+        jacks.paytable.bet = paytable.bet;
+        jacks.paytable.notes = paytable.notes;
+        Ok(jacks)
+    }
+    /// Loads a paytable file as [`Paytable::load`] does
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PaytableError> {
+        Self::from_paytable(Paytable::load(path)?)
+    }
+    /// Categories of the final hands, best first
+    pub fn categories() -> Categories {
+        let has = |cards: &[Card], rank| cards.iter().any(|card| card.rank == rank);
+        Categories::standard(5)
+            .with(Category::new("royal_flush", 1000, move |cards| {
+                cards.iter().all(|card| card.suit == cards[0].suit)
+                    && [Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::A]
+                        .into_iter()
+                        .all(|rank| has(cards, rank))
+            }))
+            .with(Category::new("jacks_or_better", 250, |cards| {
+                cards.iter().any(|card| {
+                    (card.rank >= Rank::Jack || card.rank == Rank::A)
+                        && cards.iter().filter(|c| c.rank == card.rank).count() >= 2
+                })
+            }))
+    }
+    pub fn paytable(&self) -> &Paytable {
+        &self.paytable
+    }
+}
+/// Hands of one to four cards are kept in tables indexed by the
+/// combinatorial number system over deck positions
+const SUBSET_SIZES: usize = 5;
+/// Exact Jacks or Better analysis over a single 52 card deck.
+///
+/// Building the engine counts, for every set of up to four cards, the final
+/// hands of each category that contain the set. Pricing a hold is then
+/// inclusion exclusion over the discarded cards, which needs only the 32
+/// subsets of the dealt hand, so the game is solved per suit class instead
+/// of by dealing out draws.
+pub struct VideoPoker {
+    deck: Vec<Card>,
+    position: HashMap<Card, usize>,
+    evaluator: Evaluator,
+    categories: Categories,
+    /// `binomial[n][k]`, enough for subset ranks and draw counts
+    binomial: Vec<[usize; SUBSET_SIZES + 1]>,
+    /// hands containing each set of `k` cards by category, indexed by
+    /// `rank * categories + category` in table `k`
+    containing: [Vec<u32>; SUBSET_SIZES],
+    classes: Vec<DealClass<5>>,
+}
+impl VideoPoker {
+    pub fn new() -> Self {
+        let deck = generate_deck();
+        let position = deck
+            .iter()
+            .enumerate()
+            .map(|(i, card)| (*card, i))
+            .collect::<HashMap<_, _>>();
+        let mut binomial = vec![[0; SUBSET_SIZES + 1]; deck.len() + 1];
+        for n in 0..=deck.len() {
+            binomial[n][0] = 1;
+            for k in 1..=SUBSET_SIZES.min(n) {
+                binomial[n][k] = binomial[n - 1][k - 1] + binomial[n - 1][k];
+            }
+        }
+        let evaluator = Game::standard().evaluator;
+        let categories = JacksOrBetter::categories();
+        let mut containing: [Vec<u32>; SUBSET_SIZES] =
+            std::array::from_fn(|k| vec![0; binomial[deck.len()][k] * categories.len()]);
+        let mut engine = Self {
+            deck,
+            position,
+            evaluator,
+            categories,
+            binomial,
+            containing: Default::default(),
+            classes: deal_classes::<5>(),
+        };
+        let categories = engine.categories.len();
+        let positions = (0..engine.deck.len()).collect::<Vec<_>>();
+        // every hand counts towards each of its five four card subsets
+        for_each_combination(&positions, 5, |hand| {
+            let cards: [Card; 5] = std::array::from_fn(|i| engine.deck[hand[i]]);
+            let category = engine.categories.classify(&engine.evaluator, &cards);
+            for skip in 0..5 {
+                let mut subset = [0; 4];
+                for (i, j) in (0..5).filter(|j| *j != skip).enumerate() {
+                    subset[i] = hand[j];
+                }
+                containing[4][engine.rank(&subset) * categories + category] += 1;
+            }
+        });
+        // a hand containing a set of k cards contains 5 - k of its subsets
+        // with one more card
+        for k in (0..4).rev() {
+            let (smaller, larger) = containing.split_at_mut(k + 1);
+            let (table, larger) = (&mut smaller[k], &larger[0]);
+            for_each_combination(&positions, k + 1, |superset| {
+                let from = engine.rank(superset) * categories;
+                for skip in 0..=k {
+                    let mut subset = [0; 4];
+                    for (i, j) in (0..=k).filter(|j| *j != skip).enumerate() {
+                        subset[i] = superset[j];
+                    }
+                    let to = engine.rank(&subset[..k]) * categories;
+                    for category in 0..categories {
+                        table[to + category] += larger[from + category];
+                    }
+                }
+            });
+            for count in table.iter_mut() {
+                *count /= (5 - k) as u32;
+            }
+        }
+        engine.containing = containing;
+        engine
+    }
+    /// Index of a set of sorted deck positions among the sets of its size
+    fn rank(&self, positions: &[usize]) -> usize {
+        positions
+            .iter()
+            .enumerate()
+            .map(|(i, position)| self.binomial[*position][i + 1])
+            .sum()
+    }
+    /// Total pay over every final hand of each hold mask of `cards`, in
+    /// credits
+    fn hold_pays(&self, cards: &[Card; 5], paytable: &JacksOrBetter) -> [i64; 32] {
+        let (categories, pays) = (self.categories.len(), paytable.paytable.pays());
+        let positions = cards.map(|card| self.position[&card]);
+        // total pay of the hands containing each subset of the cards
+        let mut totals = std::array::from_fn(|mask: usize| {
+            if mask == 31 {
+                return pays[self.categories.classify(&self.evaluator, cards)] as i64;
+            }
+            let mut subset = (0..5)
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| positions[i])
+                .collect::<Vec<_>>();
+            subset.sort_unstable();
+            let start = self.rank(&subset) * categories;
+            self.containing[subset.len()][start..start + categories]
+                .iter()
+                .zip(pays.iter())
+                .map(|(count, pay)| *count as i64 * *pay as i64)
+                .sum()
+        });
+        // inclusion exclusion removes the hands holding any discarded card
+        for bit in 0..5 {
+            for mask in 0..32 {
+                if mask & (1 << bit) == 0 {
+                    totals[mask] -= totals[mask | 1 << bit];
+                }
+            }
+        }
+        totals
+    }
+    /// Number of draws after keeping the cards of `mask`
+    fn draws(&self, mask: usize) -> usize {
+        self.binomial[self.deck.len() - 5][5 - mask.count_ones() as usize]
+    }
+    /// Best hold for five dealt cards, with the expected return of all 32.
+    /// Panics if a card is not in the standard deck, parse hands with
+    /// [`Deck::parse_hand`](crate::Deck::parse_hand) on
+    /// [`Deck::standard`](crate::Deck::standard) to reject jokers first.
+    pub fn optimal_play(&self, cards: [Card; 5], paytable: &JacksOrBetter) -> Play<5> {
+        let pays = self.hold_pays(&cards, paytable);
+        let holds = hold_masks::<5>()
+            .into_iter()
+            .map(|mask| {
+                let hold = std::array::from_fn(|i| (mask & (1 << i) != 0).then_some(cards[i]));
+                (
+                    hold,
+                    Rational::new(pays[mask] as i128, self.draws(mask) as i128),
+                )
+            })
+            .collect::<Vec<_>>();
+        let (hold, expected_return) =
+            holds.iter().fold(
+                holds[0],
+                |best, hold| {
+                    if hold.1 > best.1 {
+                        *hold
+                    } else {
+                        best
+                    }
+                },
+            );
+        Play {
+            hold,
+            expected_return,
+            holds,
+        }
+    }
+    /// Expected return per credit bet when all 2,598,960 deals are played
+    /// optimally. Hold returns are compared as whole numbers of `1 / lcm`
+    /// credits, where `lcm` is a common multiple of every hold's draw count.
+    pub fn expected_return(&self, paytable: &JacksOrBetter) -> Rational {
+        let lcm = (0..32)
+            .map(|mask| self.draws(mask) as i128)
+            .fold(1, |acc, n| {
+                let (mut a, mut b) = (acc, n);
+                while b != 0 {
+                    (a, b) = (b, a % b);
+                }
+                acc / a * n
+            });
+        let scale = (0..32)
+            .map(|mask| lcm / self.draws(mask) as i128)
+            .collect::<Vec<_>>();
+        let mut total = 0i128;
+        let mut deals = 0i128;
+        for class in self.classes.iter() {
+            let pays = self.hold_pays(&class.cards, paytable);
+            let best = (0..32)
+                .map(|mask| pays[mask] as i128 * scale[mask])
+                .max()
+                .expect("there are 32 holds");
+            total += best * class.weight as i128;
+            deals += class.weight as i128;
+        }
+        Rational::new(total, lcm * deals * paytable.paytable.bet as i128)
+    }
+}
+impl Default for VideoPoker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse_hand;
+    const NINE_SIX: &str = include_str!("../paytables/nine_six.toml");
+    #[test]
+    fn categories() {
+        let categories = JacksOrBetter::categories();
+        let evaluator = Game::standard().evaluator;
+        let hand =
+            |s| categories.names()[categories.classify(&evaluator, &parse_hand::<5>(s).unwrap())];
+        assert_eq!(hand("Ts Js Qs Ks As"), "royal_flush");
+        assert_eq!(hand("As 2s 3s 4s 5s"), "straight_flush");
+        assert_eq!(hand("Jc Jd 2h 5s 9c"), "jacks_or_better");
+        assert_eq!(hand("Ac Ad 2h 5s 9c"), "jacks_or_better");
+        assert_eq!(hand("Jc Jd 2h 2s 9c"), "two_pair");
+        assert_eq!(hand("Tc Td 2h 5s 9c"), "pair");
+    }
+    #[test]
+    fn paytables() {
+        let loaded = JacksOrBetter::from_paytable(Paytable::from_toml(NINE_SIX).unwrap()).unwrap();
+        assert_eq!(
+            loaded.paytable().pays(),
+            JacksOrBetter::nine_six().paytable().pays()
+        );
+        assert!(matches!(
+            JacksOrBetter::new("low royal", [1, 50, 25, 9, 6, 4, 3, 2, 1, 0, 0]),
+            Err(PaytableError::NonMonotonic { better, worse })
+                if better == "royal_flush" && worse == "straight_flush"
+        ));
+        assert!(matches!(
+            JacksOrBetter::new("short", [800, 50, 25, 9, 6, 4, 3, 2, 1, 0]),
+            Err(PaytableError::WrongCount {
+                expected: 11,
+                found: 10
+            })
+        ));
+        let standard = Paytable::with_cards("five", 5, [50, 25, 9, 6, 4, 3, 2, 1, 0]).unwrap();
+        assert!(matches!(
+            JacksOrBetter::from_paytable(standard),
+            Err(PaytableError::MissingHand(name)) if name == "royal_flush"
+        ));
+    }
+    #[test]
+    fn nine_six() {
+        let engine = VideoPoker::new();
+        let paytable = JacksOrBetter::nine_six();
+        let play = |s| engine.optimal_play(parse_hand(s).unwrap(), &paytable);
+        let royal = play("Ts Js Qs Ks As");
+        assert_eq!(royal.expected_return, Rational::from_integer(800));
+        assert_eq!(royal.holds.len(), 32);
+        // four to a flush beats a low pair, nine flush cards and three jacks
+        let flush = play("2h 5h 8h Jh 2c");
+        assert_eq!(flush.hold.iter().flatten().count(), 4);
+        assert_eq!(flush.expected_return, Rational::new(9 * 6 + 3, 47));
+        // the generic solver prices the hand the same from the categories
+        let game = Game::standard().with_categories(JacksOrBetter::categories());
+        let solved = game.optimal_play(
+            parse_hand::<5>("2h 5h 8h Jh 2c").unwrap(),
+            paytable.paytable(),
+        );
+        assert_eq!(solved.expected_return, flush.expected_return);
+        // the well known 99.54% return of full pay Jacks or Better
+        let rtp = crate::to_f64(&engine.expected_return(&paytable));
+        assert!((rtp - 0.995439).abs() < 5e-7, "{}", rtp);
+    }
+}